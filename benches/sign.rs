@@ -0,0 +1,98 @@
+//! Compare the allocations and time taken to sign and truncate.
+//!
+//! Run with `cargo bench --bench sign`.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+use totp_sos::{Algorithm, TOTP};
+
+/// Allocator that counts the number of allocations.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const ITERATIONS: u64 = 100_000;
+
+const SKEW: u8 = 2;
+
+fn measure(name: &str, f: impl Fn(u64)) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for time in 0..ITERATIONS {
+        f(time * 30);
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!(
+        "{:<24} {:>8.0} ns/iter {:>6.2} allocs/iter",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        allocations as f64 / ITERATIONS as f64,
+    );
+}
+
+fn main() {
+    let totp = TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        30,
+        "TestSecretSuperSecret".as_bytes(),
+        "mock@example.com".to_string(),
+        None,
+    )
+    .unwrap();
+
+    measure("sign", |time| {
+        black_box(totp.sign(black_box(time)).unwrap());
+    });
+    measure("truncated", |time| {
+        black_box(totp.truncated(black_box(time)).unwrap());
+    });
+    measure("generate", |time| {
+        black_box(totp.generate(black_box(time)).unwrap());
+    });
+    measure("generate_into", |time| {
+        let mut buffer = [0u8; 8];
+        black_box(totp.generate_into(black_box(time), &mut buffer).unwrap());
+    });
+
+    // Verifying with a skew signs every step in the window, compare
+    // keying the HMAC for each step with the keyed HMAC being cloned.
+    let totp = TOTP::new(
+        Algorithm::SHA1,
+        6,
+        SKEW,
+        30,
+        "TestSecretSuperSecret".as_bytes(),
+        "mock@example.com".to_string(),
+        None,
+    )
+    .unwrap();
+    measure("generate window", |time| {
+        let time = time + SKEW as u64 * 30;
+        for step in 0..=SKEW as u64 * 2 {
+            black_box(totp.generate(black_box(time - step * 30)).unwrap());
+        }
+    });
+    measure("verify", |time| {
+        black_box(totp.verify(black_box("000000"), black_box(time)));
+    });
+}
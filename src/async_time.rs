@@ -0,0 +1,107 @@
+//! Generate and verify tokens using an asynchronous time source.
+//!
+//! The [TokioTime](struct.TokioTime.html) source follows the tokio
+//! clock so tests can drive tokens deterministically with
+//! `tokio::time::pause()` and `tokio::time::advance()`.
+//!
+//! Requires the `async` feature.
+use crate::{system_time, Result, TOTP};
+use alloc::string::String;
+use core::future::Future;
+
+/// Source of the current Unix time in seconds.
+pub trait AsyncTimeSource {
+    /// The current Unix time in seconds.
+    fn now_secs(&self) -> impl Future<Output = Result<u64>> + Send;
+}
+
+/// Time source that follows the tokio clock.
+///
+/// The Unix time is anchored when the source is created and
+/// advances with `tokio::time::Instant` so a paused tokio clock
+/// also pauses the time source.
+#[derive(Debug, Clone, Copy)]
+pub struct TokioTime {
+    unix_secs: u64,
+    start: tokio::time::Instant,
+}
+
+impl TokioTime {
+    /// Create a time source anchored to the given Unix time in seconds.
+    pub fn new(unix_secs: u64) -> Self {
+        Self {
+            unix_secs,
+            start: tokio::time::Instant::now(),
+        }
+    }
+
+    /// Create a time source anchored to the current system time.
+    pub fn now() -> Result<Self> {
+        Ok(Self::new(system_time()?))
+    }
+}
+
+impl AsyncTimeSource for TokioTime {
+    async fn now_secs(&self) -> Result<u64> {
+        Ok(self.unix_secs + self.start.elapsed().as_secs())
+    }
+}
+
+impl TOTP {
+    /// Generate a token from the time of the given source.
+    pub async fn generate_current_async<T: AsyncTimeSource>(
+        &self,
+        source: &T,
+    ) -> Result<String> {
+        let t = source.now_secs().await?;
+        self.generate(t)
+    }
+
+    /// Verify if token is valid by the time of the given source,
+    /// accounting [skew](struct.TOTP.html#structfield.skew).
+    pub async fn verify_current_async<T: AsyncTimeSource>(
+        &self,
+        token: &str,
+        source: &T,
+    ) -> Result<bool> {
+        let t = source.now_secs().await?;
+        Ok(self.verify(token, t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Algorithm;
+    use alloc::string::ToString;
+    use core::time::Duration;
+
+    fn totp() -> TOTP {
+        TOTP::new(
+            Algorithm::SHA1,
+            6,
+            0,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn generate_current_async() {
+        let totp = totp();
+        let source = TokioTime::new(1000);
+        let token = totp.generate_current_async(&source).await.unwrap();
+        assert_eq!(token, totp.generate(1000).unwrap());
+        assert!(totp.verify_current_async(&token, &source).await.unwrap());
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(
+            totp.generate_current_async(&source).await.unwrap(),
+            totp.generate(1030).unwrap()
+        );
+        assert!(!totp.verify_current_async(&token, &source).await.unwrap());
+    }
+}
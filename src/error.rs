@@ -1,12 +1,26 @@
+use alloc::{boxed::Box, string::String};
 use thiserror::Error;
 
 /// Errors generated by the library.
+///
+/// New variants may be added without a breaking change so
+/// a `match` must include a wildcard arm.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     /// Error generated when a secret is not valid base32.
-    #[error("Secret '{0}' is not a valid non-padded base32 string")]
+    #[error("Secret '{0}' is not a valid base32 string")]
     Secret(String),
 
+    /// Error generated when the length of a base32 secret does not
+    /// encode a whole number of bytes.
+    #[error("Secret of {0} base32 characters does not encode a whole number of bytes")]
+    SecretLength(usize),
+
+    /// Error generated when a secret is not valid hex.
+    #[error("Secret '{0}' is not a valid hex string")]
+    Hex(String),
+
     /// Error generated an issuer mismatch is detected.
     #[error("An issuer '{0}' could be retrieved from the path, but a different issuer '{1}' was found in the issuer URL parameter")]
     IssuerMismatch(String, String),
@@ -23,6 +37,10 @@ pub enum Error {
     #[error("Could not parse step '{0}' as a number")]
     Step(String),
 
+    /// Error generated T0 cannot be parsed as a number.
+    #[error("Could not parse T0 '{0}' as a number")]
+    T0(String),
+
     /// Error generated digits cannot be parsed as a number.
     #[error("Could not parse digits '{0}' as a number")]
     Digits(String),
@@ -31,6 +49,10 @@ pub enum Error {
     #[error("Algorithm can only be SHA1, SHA256 or SHA512, not '{0}'")]
     Algorithm(String),
 
+    /// Error generated when an environment variable string is malformed.
+    #[error("Could not parse environment variable string '{0}'")]
+    EnvString(String),
+
     /// Error generated when decoding a URL.
     #[error("Could not decode URL '{0}'")]
     IssuerDecoding(String),
@@ -39,23 +61,94 @@ pub enum Error {
     #[error("Host should be totp, not '{0}'")]
     Host(String),
 
+    /// Error generated when an imported entry is not a TOTP,
+    /// for example an HOTP entry which uses a counter.
+    #[error("Unsupported OTP type '{0}', only TOTP can be imported")]
+    UnsupportedType(String),
+
+    /// Error generated when a URL parameter that changes the tokens
+    /// is repeated.
+    #[error("URL parameter '{0}' must not be repeated")]
+    DuplicateParam(String),
+
     /// Error generated when a URL scheme is invalid.
     #[error("Scheme should be otpauth, not '{0}'")]
     Scheme(String),
 
-    /// Error generated when the length of the shared secret is not at least 128 bits.
-    #[error("The length of the shared secret MUST be at least 128 bits; {0} bits is not enough")]
+    /// Error generated when the length of the shared secret is not at least 128 bits,
+    /// or not the recommended length for the algorithm when using strict validation.
+    #[error("The length of the shared secret MUST be at least 128 bits (or the algorithm output size when strict); {0} bits is not enough")]
     SecretTooSmall(usize),
 
     /// Error generated when the number of digits is not in the valid range.
     #[error("Implementations MUST extract a 6-digit code at a minimum and possibly 7 and 8-digit code; {0} digits is not allowed")]
     InvalidDigits(usize),
 
+    /// Error generated when the HMAC cannot be keyed with the secret.
+    #[error("The secret could not be used as an HMAC key")]
+    Hmac,
+
+    /// Error generated when the skew is more than the maximum.
+    #[error(
+        "The skew must not be more than {max} steps; {0} is not allowed",
+        max = crate::MAX_SKEW
+    )]
+    SkewTooLarge(u8),
+
+    /// Error generated when the step is 0.
+    #[error("The step must be at least 1 second; {0} is not allowed")]
+    InvalidStep(u64),
+
+    /// Error generated when a binary encoded TOTP is malformed.
+    #[error("Could not decode TOTP bytes: {0}")]
+    Decode(String),
+
+    /// Error generated when a signature is too short to be truncated.
+    #[error("The signature must be at least 20 bytes, not {0}")]
+    SignatureLength(usize),
+
+    /// Error generated when a value imported from a URL is not valid,
+    /// for example a secret that is too short; the source is the
+    /// validation error.
+    #[error("Could not import URL: {0}")]
+    UrlImport(#[source] Box<Error>),
+
+    /// Error generated when a buffer is shorter than a token.
+    #[error("The buffer must be at least {0} bytes to hold the token")]
+    BufferTooSmall(usize),
+
+    /// Error generated when a date time is before the Unix epoch.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    #[error("Timestamp {0} is before the Unix epoch")]
+    Timestamp(i64),
+
+    /// Error generated when a QR code cannot be rendered.
+    #[cfg(feature = "qr")]
+    #[error("Could not generate QR code: {0}")]
+    Qr(String),
+
+    /// Error generated when an exported entry is malformed.
+    #[cfg(feature = "export")]
+    #[error("Could not convert exported entry: {0}")]
+    Export(String),
+
+    /// Error generated when a migration payload is malformed.
+    #[cfg(feature = "migration")]
+    #[error("Could not decode migration payload: {0}")]
+    Migration(String),
+
+    /// Error generated when an OCRA suite is not supported or a
+    /// challenge does not match the suite.
+    #[cfg(feature = "ocra")]
+    #[error("OCRA error: {0}")]
+    Ocra(String),
+
     /// Errors generated by the URL library.
     #[error(transparent)]
     Url(#[from] url::ParseError),
 
     /// Errors generated by the system time.
+    #[cfg(feature = "std")]
     #[error(transparent)]
     Time(#[from] std::time::SystemTimeError),
 }
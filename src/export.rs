@@ -0,0 +1,283 @@
+//! Convert to and from the JSON entries exported by the Aegis and
+//! andOTP authenticator apps.
+//!
+//! Only the fields needed to generate tokens are mapped; the secret is
+//! base32 and, like [from_url](../struct.TOTP.html#method.from_url),
+//! imported entries have a skew of 0. The JSON values contain the
+//! secret so they should be handled with the same care as the TOTP.
+//!
+//! Requires the `export` feature.
+use crate::{decode_secret, Error, Result, STEAM_DIGITS, TOTP};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use serde_json::{json, Value};
+
+/// Read a string field of an entry.
+fn str_field<'a>(entry: &'a Value, key: &str) -> Result<&'a str> {
+    entry
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::Export(format!("missing string '{}'", key)))
+}
+
+/// Read a number field of an entry.
+fn u64_field(entry: &Value, key: &str) -> Result<u64> {
+    entry
+        .get(key)
+        .and_then(Value::as_u64)
+        .ok_or_else(|| Error::Export(format!("missing number '{}'", key)))
+}
+
+/// Convert an empty issuer to None.
+fn issuer(issuer: &str) -> Option<String> {
+    if issuer.is_empty() {
+        None
+    } else {
+        Some(issuer.to_string())
+    }
+}
+
+/// Whether the entry type is Steam, HOTP entries are rejected
+/// as their tokens use a counter.
+fn is_steam(kind: &str) -> Result<bool> {
+    if kind.eq_ignore_ascii_case("totp") {
+        Ok(false)
+    } else if kind.eq_ignore_ascii_case("steam") {
+        Ok(true)
+    } else if kind.eq_ignore_ascii_case("hotp") {
+        Err(Error::UnsupportedType("hotp".to_string()))
+    } else {
+        Err(Error::Export(format!("unsupported type '{}'", kind)))
+    }
+}
+
+/// Validate the values of an entry the same as [TOTP::new].
+fn entry_totp(
+    algorithm: &str,
+    digits: u64,
+    step: u64,
+    secret: &str,
+    account_name: &str,
+    issuer: Option<String>,
+    steam: bool,
+) -> Result<TOTP> {
+    // Steam entries have 5 digits which are ignored in Steam mode.
+    let digits = if steam && digits == STEAM_DIGITS as u64 {
+        6
+    } else {
+        usize::try_from(digits)
+            .map_err(|_| Error::Digits(digits.to_string()))?
+    };
+    Ok(TOTP::new(
+        algorithm.parse()?,
+        digits,
+        0,
+        step,
+        decode_secret(secret)?,
+        account_name.to_string(),
+        issuer,
+    )?
+    .with_steam(steam))
+}
+
+impl TOTP {
+    /// Type and digits of an exported entry.
+    fn entry_kind(&self) -> (&'static str, usize) {
+        if self.is_steam() {
+            ("steam", STEAM_DIGITS)
+        } else {
+            ("totp", self.digits.get())
+        }
+    }
+
+    /// Convert to an entry of an Aegis vault.
+    ///
+    /// The entry has no `uuid`, which Aegis generates when the
+    /// entry is imported.
+    pub fn to_aegis_entry(&self) -> Value {
+        let (kind, digits) = self.entry_kind();
+        json!({
+            "type": kind,
+            "name": self.account_name,
+            "issuer": self.issuer.as_deref().unwrap_or_default(),
+            "note": "",
+            "icon": null,
+            "info": {
+                "secret": self.to_secret_base32(),
+                "algo": self.algorithm.to_string(),
+                "digits": digits,
+                "period": self.step,
+            },
+        })
+    }
+
+    /// Convert an entry of an Aegis vault.
+    ///
+    /// Malformed entries are reported as [Error::Export](enum.Error.html#variant.Export)
+    /// and HOTP entries as [Error::UnsupportedType](enum.Error.html#variant.UnsupportedType);
+    /// the values are validated the same as [new](struct.TOTP.html#method.new).
+    pub fn from_aegis_entry(entry: &Value) -> Result<TOTP> {
+        let steam = is_steam(str_field(entry, "type")?)?;
+        let info = entry.get("info").ok_or_else(|| {
+            Error::Export("missing object 'info'".to_string())
+        })?;
+        entry_totp(
+            str_field(info, "algo")?,
+            u64_field(info, "digits")?,
+            u64_field(info, "period")?,
+            str_field(info, "secret")?,
+            str_field(entry, "name")?,
+            issuer(str_field(entry, "issuer").unwrap_or_default()),
+            steam,
+        )
+    }
+
+    /// Convert to an entry of an andOTP backup.
+    pub fn to_andotp_entry(&self) -> Value {
+        let (kind, digits) = self.entry_kind();
+        json!({
+            "secret": self.to_secret_base32(),
+            "issuer": self.issuer.as_deref().unwrap_or_default(),
+            "label": self.account_name,
+            "digits": digits,
+            "type": kind.to_ascii_uppercase(),
+            "algorithm": self.algorithm.to_string(),
+            "thumbnail": "Default",
+            "last_used": 0,
+            "used_frequency": 0,
+            "period": self.step,
+            "tags": [],
+        })
+    }
+
+    /// Convert an entry of an andOTP backup.
+    ///
+    /// Errors are the same as [from_aegis_entry](struct.TOTP.html#method.from_aegis_entry).
+    pub fn from_andotp_entry(entry: &Value) -> Result<TOTP> {
+        let steam = is_steam(str_field(entry, "type")?)?;
+        entry_totp(
+            str_field(entry, "algorithm")?,
+            u64_field(entry, "digits")?,
+            u64_field(entry, "period")?,
+            str_field(entry, "secret")?,
+            str_field(entry, "label")?,
+            issuer(str_field(entry, "issuer").unwrap_or_default()),
+            steam,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "sha2")]
+    use crate::Algorithm;
+
+    #[cfg(feature = "sha2")]
+    fn totp() -> TOTP {
+        TOTP::new(
+            Algorithm::SHA256,
+            8,
+            0,
+            60,
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn aegis_entry() {
+        let totp = totp();
+        let entry = totp.to_aegis_entry();
+        assert_eq!(
+            entry,
+            json!({
+                "type": "totp",
+                "name": "mock@example.com",
+                "issuer": "Github",
+                "note": "",
+                "icon": null,
+                "info": {
+                    "secret": "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+                    "algo": "SHA256",
+                    "digits": 8,
+                    "period": 60,
+                },
+            })
+        );
+        assert_eq!(TOTP::from_aegis_entry(&entry).unwrap(), totp);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn andotp_entry() {
+        let totp = totp();
+        let entry = totp.to_andotp_entry();
+        assert_eq!(entry["type"], "TOTP");
+        assert_eq!(entry["label"], "mock@example.com");
+        assert_eq!(entry["algorithm"], "SHA256");
+        assert_eq!(entry["digits"], 8);
+        assert_eq!(entry["period"], 60);
+        assert_eq!(TOTP::from_andotp_entry(&entry).unwrap(), totp);
+    }
+
+    #[test]
+    fn steam_entry() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock".to_string(),
+            Some("Steam".to_string()),
+        )
+        .unwrap()
+        .with_skew(0)
+        .unwrap()
+        .with_steam(true);
+        let entry = totp.to_aegis_entry();
+        assert_eq!(entry["type"], "steam");
+        assert_eq!(entry["info"]["digits"], 5);
+        assert_eq!(TOTP::from_aegis_entry(&entry).unwrap(), totp);
+        let entry = totp.to_andotp_entry();
+        assert_eq!(entry["type"], "STEAM");
+        assert_eq!(TOTP::from_andotp_entry(&entry).unwrap(), totp);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn invalid_entries() {
+        let mut entry = totp().to_aegis_entry();
+        entry["type"] = json!("hotp");
+        assert!(matches!(
+            TOTP::from_aegis_entry(&entry),
+            Err(Error::UnsupportedType(_))
+        ));
+        entry["type"] = json!("yandex");
+        assert!(matches!(
+            TOTP::from_aegis_entry(&entry),
+            Err(Error::Export(_))
+        ));
+        entry["type"] = json!("totp");
+        entry["info"]["algo"] = json!("MD5");
+        assert!(matches!(
+            TOTP::from_aegis_entry(&entry),
+            Err(Error::Algorithm(_))
+        ));
+        entry["info"]["algo"] = json!("SHA1");
+        entry["info"]["digits"] = json!(9);
+        assert!(matches!(
+            TOTP::from_aegis_entry(&entry),
+            Err(Error::InvalidDigits(9))
+        ));
+
+        let mut entry = totp().to_andotp_entry();
+        entry.as_object_mut().unwrap().remove("secret");
+        assert!(matches!(
+            TOTP::from_andotp_entry(&entry),
+            Err(Error::Export(_))
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+//! Sign tokens with an HMAC algorithm provided by the caller.
+use crate::{truncate, Algorithm, Error, Result, Secret, TOTP};
+use alloc::{string::String, vec::Vec};
+
+/// Minimum length of a signature in bytes so that it can be
+/// truncated as per [rfc-4226](https://tools.ietf.org/html/rfc4226#section-5.3).
+const MIN_SIGNATURE_LEN: usize = 20;
+
+/// HMAC algorithm used to sign the counter.
+///
+/// Implement this trait to generate tokens with an algorithm
+/// that is not one of the built-in [Algorithm](enum.Algorithm.html)
+/// variants, for example SM3-HMAC, and pass it to
+/// [generate_with_hasher](struct.TOTP.html#method.generate_with_hasher)
+/// or [verify_with_hasher](struct.TOTP.html#method.verify_with_hasher),
+/// or keep it with the TOTP in a [HasherTotp](struct.HasherTotp.html).
+pub trait OtpHasher {
+    /// Name of the algorithm, for example `SHA1`.
+    fn name(&self) -> &str;
+
+    /// Sign the message with the key.
+    ///
+    /// The signature must be at least 20 bytes long.
+    fn sign(&self, key: &[u8], msg: &[u8]) -> Result<Vec<u8>>;
+}
+
+impl OtpHasher for Algorithm {
+    fn name(&self) -> &str {
+        match self {
+            Algorithm::SHA1 => "SHA1",
+            #[cfg(feature = "sha2")]
+            Algorithm::SHA256 => "SHA256",
+            #[cfg(feature = "sha2")]
+            Algorithm::SHA512 => "SHA512",
+        }
+    }
+
+    fn sign(&self, key: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+        Ok(Algorithm::sign(self, key, msg)?.into_inner())
+    }
+}
+
+impl TOTP {
+    /// Truncated signature of the counter using the hasher.
+    fn truncated_with_hasher(
+        &self,
+        hasher: &dyn OtpHasher,
+        counter: u64,
+    ) -> Result<u32> {
+        let signature = Secret::from(
+            hasher.sign(&self.secret, &self.counter_bytes(counter))?,
+        );
+        if signature.len() < MIN_SIGNATURE_LEN {
+            return Err(Error::SignatureLength(signature.len()));
+        }
+        Ok(truncate(&signature))
+    }
+
+    /// Generate a token given the provided timestamp in seconds
+    /// signing with the hasher instead of the
+    /// [algorithm](struct.TOTP.html#structfield.algorithm).
+    ///
+    /// Returns [Error::SignatureLength](enum.Error.html#variant.SignatureLength)
+    /// if the signature is shorter than 20 bytes.
+    pub fn generate_with_hasher(
+        &self,
+        hasher: &dyn OtpHasher,
+        time: u64,
+    ) -> Result<String> {
+        Ok(self.encode_token(
+            self.truncated_with_hasher(hasher, self.checked_counter(time)?)?,
+        ))
+    }
+
+    /// Verify if token is valid given the provided timestamp in
+    /// seconds, accounting [skew](struct.TOTP.html#structfield.skew),
+    /// signing with the hasher instead of the
+    /// [algorithm](struct.TOTP.html#structfield.algorithm).
+    pub fn verify_with_hasher(
+        &self,
+        hasher: &dyn OtpHasher,
+        token: &str,
+        time: u64,
+    ) -> bool {
+        let token = token.as_bytes();
+        if !self.is_token(token) {
+            return false;
+        }
+        let skew = self.window();
+        self.find_step_with(
+            token,
+            time,
+            skew.before as u64,
+            skew.after as u64,
+            |step| {
+                self.truncated_with_hasher(hasher, step)
+                    .ok()
+                    .map(|result| self.encode_token(result))
+            },
+        )
+        .is_some()
+    }
+
+    /// Keep the hasher with the TOTP so it signs every token
+    /// without being passed to each call.
+    pub fn with_hasher<H: OtpHasher>(self, hasher: H) -> HasherTotp<H> {
+        HasherTotp {
+            inner: self,
+            hasher,
+        }
+    }
+}
+
+/// TOTP that signs with the hasher it holds instead of the
+/// [algorithm](struct.TOTP.html#structfield.algorithm).
+///
+/// Created with [TOTP::with_hasher](struct.TOTP.html#method.with_hasher).
+#[derive(Clone, Debug)]
+pub struct HasherTotp<H> {
+    inner: TOTP,
+    hasher: H,
+}
+
+impl<H: OtpHasher> HasherTotp<H> {
+    /// Generate a token given the provided timestamp in seconds,
+    /// see [generate_with_hasher](struct.TOTP.html#method.generate_with_hasher).
+    pub fn generate(&self, time: u64) -> Result<String> {
+        self.inner.generate_with_hasher(&self.hasher, time)
+    }
+
+    /// Verify if token is valid given the provided timestamp in seconds,
+    /// see [verify_with_hasher](struct.TOTP.html#method.verify_with_hasher).
+    pub fn verify(&self, token: &str, time: u64) -> bool {
+        self.inner.verify_with_hasher(&self.hasher, token, time)
+    }
+
+    /// The hasher used to sign tokens.
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// The TOTP with the parameters of the tokens.
+    pub fn totp(&self) -> &TOTP {
+        &self.inner
+    }
+
+    /// Convert into the [TOTP](struct.TOTP.html), dropping the hasher.
+    pub fn into_inner(self) -> TOTP {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec};
+
+    /// SHA1 with a different name to check a custom hasher is used.
+    #[cfg(feature = "sha2")]
+    struct Custom;
+
+    #[cfg(feature = "sha2")]
+    impl OtpHasher for Custom {
+        fn name(&self) -> &str {
+            "CUSTOM"
+        }
+
+        fn sign(&self, key: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+            OtpHasher::sign(&Algorithm::SHA1, key, msg)
+        }
+    }
+
+    /// Signature that is too short to be truncated.
+    struct Short;
+
+    impl OtpHasher for Short {
+        fn name(&self) -> &str {
+            "SHORT"
+        }
+
+        fn sign(&self, _key: &[u8], _msg: &[u8]) -> Result<Vec<u8>> {
+            Ok(vec![0; 16])
+        }
+    }
+
+    fn totp(algorithm: Algorithm) -> TOTP {
+        TOTP::new(
+            algorithm,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn builtin_hasher() {
+        for algorithm in
+            [Algorithm::SHA1, Algorithm::SHA256, Algorithm::SHA512]
+        {
+            let totp = totp(algorithm);
+            assert_eq!(algorithm.name(), algorithm.to_string());
+            assert_eq!(
+                totp.generate_with_hasher(&algorithm, 1000).unwrap(),
+                totp.generate(1000).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn custom_hasher() {
+        // The algorithm field is ignored.
+        let totp = totp(Algorithm::SHA512);
+        let token = totp.generate_with_hasher(&Custom, 1000).unwrap();
+        assert_eq!(
+            token,
+            self::totp(Algorithm::SHA1).generate(1000).unwrap()
+        );
+        assert!(totp.verify_with_hasher(&Custom, &token, 1030));
+        assert!(!totp.verify_with_hasher(&Custom, &token, 1090));
+        assert!(!totp.verify(&token, 1000));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn holds_hasher() {
+        let totp = totp(Algorithm::SHA512).with_hasher(Custom);
+        assert_eq!(totp.hasher().name(), "CUSTOM");
+        let token = totp.generate(1000).unwrap();
+        assert_eq!(
+            token,
+            totp.totp().generate_with_hasher(&Custom, 1000).unwrap()
+        );
+        assert!(totp.verify(&token, 1030));
+        assert!(!totp.verify(&token, 1090));
+        let totp = totp.into_inner();
+        assert!(!totp.verify(&token, 1000));
+    }
+
+    #[test]
+    fn short_signature() {
+        let totp = totp(Algorithm::SHA1);
+        assert!(matches!(
+            totp.generate_with_hasher(&Short, 1000),
+            Err(Error::SignatureLength(16))
+        ));
+        assert!(!totp.verify_with_hasher(&Short, "000000", 1000));
+        assert!(!totp.with_hasher(Short).verify("000000", 1000));
+    }
+}
@@ -1,96 +1,894 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
 
 //! This library was a fork of `totp-rs` that is no longer maintained, instead all the features 
 //! we needed have been merged into `totp-rs` so use that crate instead.
+//!
+//! The `std` feature is enabled by default; without it the crate is
+//! `no_std` and requires `alloc`, the functions that read the system
+//! time (`*_current`, `ttl`) are not available so a timestamp must be
+//! supplied by the caller.
+//!
+//! The `sha2` feature is also enabled by default and provides the
+//! SHA256 and SHA512 algorithms; disable it for a smaller SHA1-only
+//! build, URLs that use the other algorithms are then rejected.
+//!
+//! The `normalize` feature normalizes account names and issuers to
+//! Unicode NFC so that composed and decomposed characters compare
+//! equal, for example the issuer of the label and the query of a URL.
+//!
+//! The `chrono` feature adds functions that take a
+//! `chrono::DateTime<Utc>` instead of a timestamp in seconds and
+//! the `time` feature functions that take a `time::OffsetDateTime`.
+
+extern crate alloc;
 
 mod error;
+mod hasher;
+mod secret;
+mod typed;
 
+#[cfg(feature = "async")]
+pub mod async_time;
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(feature = "migration")]
+pub mod migration;
+#[cfg(feature = "ocra")]
+pub mod ocra;
 #[cfg(feature = "qr")]
 pub mod qr;
+#[cfg(feature = "serde")]
+pub mod serde_url;
 
 pub use error::Error;
+pub use hasher::{HasherTotp, OtpHasher};
+pub use secret::Secret;
+pub use typed::Totp;
 
 /// Result type for the TOTP library.
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
 use constant_time_eq::constant_time_eq;
-use hmac::Mac;
-use std::{
+use core::{
     fmt,
-    time::{SystemTime, UNIX_EPOCH},
+    hash::{Hash, Hasher},
+    ops::RangeInclusive,
+    str::FromStr,
 };
+use hmac::Mac;
+use percent_encoding::{
+    percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC,
+};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(feature = "time")]
+use time::OffsetDateTime;
 use url::{Host, Url};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 type HmacSha1 = hmac::Hmac<sha1::Sha1>;
+#[cfg(feature = "sha2")]
 type HmacSha256 = hmac::Hmac<sha2::Sha256>;
+#[cfg(feature = "sha2")]
 type HmacSha512 = hmac::Hmac<sha2::Sha512>;
 
+/// Characters that are percent-encoded in the account name and issuer,
+/// everything except the RFC 3986 unreserved characters.
+const ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Prefix for the environment variable representation.
+const ENV_PREFIX: &str = "TOTP_v2";
+/// Prefix of the first environment variable representation
+/// which has no trailing fields.
+const ENV_PREFIX_V1: &str = "TOTP_v1";
+const BYTES_VERSION: u8 = 2;
+
+/// Option flag in the binary format for Steam mode.
+const BYTES_STEAM: u8 = 1;
+/// Option flag in the binary format for a little-endian counter.
+const BYTES_LITTLE_ENDIAN: u8 = 1 << 1;
+/// Option flag in the binary format for a skew above [MAX_SKEW].
+const BYTES_UNCHECKED_SKEW: u8 = 1 << 2;
+/// Option flag in the binary format for a colon in the label.
+const BYTES_UNCHECKED_LABEL: u8 = 1 << 3;
+
+/// Alphabet used by Steam Guard codes.
+const STEAM_CHARS: &[u8] = b"23456789BCDFGHJKMNPQRTVWXY";
+
+/// Number of characters in a Steam Guard code.
+const STEAM_DIGITS: usize = 5;
+
+/// Algorithms available with the enabled features.
+const ALGORITHMS: &[Algorithm] = &[
+    Algorithm::SHA1,
+    #[cfg(feature = "sha2")]
+    Algorithm::SHA256,
+    #[cfg(feature = "sha2")]
+    Algorithm::SHA512,
+];
+
+/// Largest [skew](struct.TOTP.html#structfield.skew) accepted by
+/// [TOTP::new](struct.TOTP.html#method.new) and
+/// [with_skew](struct.TOTP.html#method.with_skew).
+pub const MAX_SKEW: u8 = 10;
+
 /// Algorithm enum holds the three standards algorithms for TOTP as per the [reference implementation](https://tools.ietf.org/html/rfc6238#appendix-A)
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///
+/// The variants depend on the enabled features so a `match` must
+/// include a wildcard arm.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum Algorithm {
     /// The SHA1 algorithm.
+    #[default]
     SHA1,
-    /// The SHA256 algorithm.
+    /// The SHA256 algorithm, requires the `sha2` feature.
+    #[cfg(feature = "sha2")]
     SHA256,
-    /// The SHA512 algorithm.
+    /// The SHA512 algorithm, requires the `sha2` feature.
+    #[cfg(feature = "sha2")]
     SHA512,
 }
 
-impl std::default::Default for Algorithm {
-    fn default() -> Self {
-        Algorithm::SHA1
-    }
-}
-
 impl fmt::Display for Algorithm {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Algorithm::SHA1 => f.write_str("SHA1"),
+            #[cfg(feature = "sha2")]
             Algorithm::SHA256 => f.write_str("SHA256"),
+            #[cfg(feature = "sha2")]
             Algorithm::SHA512 => f.write_str("SHA512"),
         }
     }
 }
 
+impl FromStr for Algorithm {
+    type Err = Error;
+
+    /// Parse an algorithm name, case-insensitively.
+    ///
+    /// SHA256 and SHA512 are errors without the `sha2` feature.
+    fn from_str(s: &str) -> Result<Self> {
+        if s.eq_ignore_ascii_case("SHA1") {
+            return Ok(Algorithm::SHA1);
+        }
+        #[cfg(feature = "sha2")]
+        if s.eq_ignore_ascii_case("SHA256") {
+            return Ok(Algorithm::SHA256);
+        }
+        #[cfg(feature = "sha2")]
+        if s.eq_ignore_ascii_case("SHA512") {
+            return Ok(Algorithm::SHA512);
+        }
+        Err(Error::Algorithm(s.to_string()))
+    }
+}
+
+impl TryFrom<&str> for Algorithm {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        value.parse()
+    }
+}
+
 impl Algorithm {
-    fn hash<D>(mut digest: D, data: &[u8]) -> Vec<u8>
+    /// Length in bytes of the HMAC output.
+    pub fn output_len(&self) -> usize {
+        match self {
+            Algorithm::SHA1 => 20,
+            #[cfg(feature = "sha2")]
+            Algorithm::SHA256 => 32,
+            #[cfg(feature = "sha2")]
+            Algorithm::SHA512 => 64,
+        }
+    }
+
+    /// Recommended secret length in bytes which matches the HMAC
+    /// output size as per [rfc-6238](https://tools.ietf.org/html/rfc6238#appendix-A).
+    fn recommended_secret_len(&self) -> usize {
+        self.output_len()
+    }
+
+    fn hash<D>(mut digest: D, data: &[u8]) -> Secret
     where
         D: Mac,
     {
         digest.update(data);
-        digest.finalize().into_bytes().to_vec()
+        #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+        let mut bytes = digest.finalize().into_bytes();
+        let signature = Secret::from(bytes.to_vec());
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(bytes.as_mut_slice());
+        signature
     }
 
-    fn sign(&self, key: &[u8], data: &[u8]) -> Vec<u8> {
-        match self {
-            Algorithm::SHA1 => {
-                Algorithm::hash(HmacSha1::new_from_slice(key).unwrap(), data)
-            }
-            Algorithm::SHA256 => Algorithm::hash(
-                HmacSha256::new_from_slice(key).unwrap(),
-                data,
+    /// Truncate the signature without copying it out of the HMAC output.
+    fn hash_truncated<D>(mut digest: D, data: &[u8]) -> u32
+    where
+        D: Mac,
+    {
+        digest.update(data);
+        #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+        let mut bytes = digest.finalize().into_bytes();
+        let result = truncate(&bytes);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(bytes.as_mut_slice());
+        result
+    }
+
+    /// Key the HMAC with the secret.
+    fn keyed(&self, key: &[u8]) -> Result<KeyedMac> {
+        Ok(match self {
+            Algorithm::SHA1 => KeyedMac::SHA1(
+                HmacSha1::new_from_slice(key).map_err(|_| Error::Hmac)?,
             ),
-            Algorithm::SHA512 => Algorithm::hash(
-                HmacSha512::new_from_slice(key).unwrap(),
-                data,
+            #[cfg(feature = "sha2")]
+            Algorithm::SHA256 => KeyedMac::SHA256(
+                HmacSha256::new_from_slice(key).map_err(|_| Error::Hmac)?,
             ),
+            #[cfg(feature = "sha2")]
+            Algorithm::SHA512 => KeyedMac::SHA512(
+                HmacSha512::new_from_slice(key).map_err(|_| Error::Hmac)?,
+            ),
+        })
+    }
+
+    fn sign_truncated(&self, key: &[u8], data: &[u8]) -> Result<u32> {
+        Ok(self.keyed(key)?.sign_truncated(data))
+    }
+
+    fn sign(&self, key: &[u8], data: &[u8]) -> Result<Secret> {
+        Ok(self.keyed(key)?.sign(data))
+    }
+}
+
+/// HMAC keyed with a secret.
+///
+/// Expanding the key is the most expensive part of signing so the
+/// keyed state is cloned for each message when several counters
+/// are signed with the same secret.
+#[derive(Clone)]
+#[allow(clippy::upper_case_acronyms)]
+enum KeyedMac {
+    SHA1(HmacSha1),
+    #[cfg(feature = "sha2")]
+    SHA256(HmacSha256),
+    #[cfg(feature = "sha2")]
+    SHA512(HmacSha512),
+}
+
+impl KeyedMac {
+    fn sign(&self, data: &[u8]) -> Secret {
+        match self {
+            KeyedMac::SHA1(mac) => Algorithm::hash(mac.clone(), data),
+            #[cfg(feature = "sha2")]
+            KeyedMac::SHA256(mac) => Algorithm::hash(mac.clone(), data),
+            #[cfg(feature = "sha2")]
+            KeyedMac::SHA512(mac) => Algorithm::hash(mac.clone(), data),
+        }
+    }
+
+    fn sign_truncated(&self, data: &[u8]) -> u32 {
+        match self {
+            KeyedMac::SHA1(mac) => {
+                Algorithm::hash_truncated(mac.clone(), data)
+            }
+            #[cfg(feature = "sha2")]
+            KeyedMac::SHA256(mac) => {
+                Algorithm::hash_truncated(mac.clone(), data)
+            }
+            #[cfg(feature = "sha2")]
+            KeyedMac::SHA512(mac) => {
+                Algorithm::hash_truncated(mac.clone(), data)
+            }
+        }
+    }
+}
+
+/// Number of digits in a token.
+///
+/// Per [rfc-4226](https://tools.ietf.org/html/rfc4226#section-5.3)
+/// a token has between 6 and 8 digits, other values cannot be
+/// represented. Compares equal to the same `usize`.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "usize", into = "usize")
+)]
+pub enum Digits {
+    /// 6 digits.
+    #[default]
+    Six,
+    /// 7 digits.
+    Seven,
+    /// 8 digits.
+    Eight,
+}
+
+impl Digits {
+    /// The number of digits.
+    pub fn get(&self) -> usize {
+        match self {
+            Digits::Six => 6,
+            Digits::Seven => 7,
+            Digits::Eight => 8,
+        }
+    }
+
+    /// Ten to the power of the number of digits.
+    fn modulus(&self) -> u32 {
+        match self {
+            Digits::Six => 1_000_000,
+            Digits::Seven => 10_000_000,
+            Digits::Eight => 100_000_000,
+        }
+    }
+}
+
+impl TryFrom<usize> for Digits {
+    type Error = Error;
+
+    fn try_from(value: usize) -> Result<Self> {
+        match value {
+            6 => Ok(Digits::Six),
+            7 => Ok(Digits::Seven),
+            8 => Ok(Digits::Eight),
+            _ => Err(Error::InvalidDigits(value)),
+        }
+    }
+}
+
+impl From<Digits> for usize {
+    fn from(value: Digits) -> Self {
+        value.get()
+    }
+}
+
+impl PartialEq<usize> for Digits {
+    fn eq(&self, other: &usize) -> bool {
+        self.get() == *other
+    }
+}
+
+impl fmt::Display for Digits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
+
+/// Strength of a secret compared to the lengths recommended by
+/// [rfc-4226](https://tools.ietf.org/html/rfc4226#section-4) and
+/// [rfc-6238](https://tools.ietf.org/html/rfc6238#appendix-A).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SecretStrength {
+    /// Shorter than the 128 bit minimum.
+    BelowMinimum,
+    /// At least 128 bits but shorter than the HMAC output
+    /// size of the algorithm.
+    Minimum,
+    /// At least the HMAC output size of the algorithm.
+    Recommended,
+}
+
+/// Number of steps accepted before and after the current step.
+///
+/// Clients' clocks are more often behind than ahead of a server
+/// so it can be useful to accept more steps before than after.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Skew {
+    /// Number of steps before the current step.
+    pub before: u8,
+    /// Number of steps after the current step.
+    pub after: u8,
+}
+
+/// Byte order of the counter when it is signed.
+///
+/// [rfc-4226](https://tools.ietf.org/html/rfc4226#section-5.2) requires
+/// the counter to be big-endian which is the only compliant choice;
+/// little-endian exists only to test against broken devices.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Endianness {
+    /// Big-endian, as per the specification.
+    #[default]
+    Big,
+    /// Little-endian, not compliant with the specification.
+    Little,
+}
+
+/// Parameters of a TOTP without the secret or the label.
+///
+/// This is `Copy` so it can be passed around, compared and logged
+/// without cloning or exposing the secret.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TotpConfig {
+    /// SHA-1 is the most widespread algorithm used.
+    pub algorithm: Algorithm,
+    /// The number of digits for the auth code.
+    pub digits: Digits,
+    /// Number of steps allowed as network delay.
+    pub skew: u8,
+    /// Duration in seconds of a step.
+    pub step: u64,
+}
+
+/// Encode a truncated value as a Steam Guard token.
+fn steam_token(result: u32) -> String {
+    let mut result = result as usize;
+    let mut code = String::with_capacity(STEAM_DIGITS);
+    for _ in 0..STEAM_DIGITS {
+        code.push(STEAM_CHARS[result % STEAM_CHARS.len()] as char);
+        result /= STEAM_CHARS.len();
+    }
+    code
+}
+
+/// Dynamic truncation of a signature as per [rfc-4226](https://tools.ietf.org/html/rfc4226#section-5.3).
+fn truncate(signature: &[u8]) -> u32 {
+    let offset = (signature.last().unwrap() & 15) as usize;
+    u32::from_be_bytes(signature[offset..offset + 4].try_into().unwrap())
+        & 0x7fff_ffff
+}
+
+/// Check that the length of a base32 string, ignoring padding,
+/// encodes a whole number of bytes.
+///
+/// Each character is 5 bits so a remainder of 1, 3 or 6 characters
+/// in the last group of 8 cannot be decoded without dropping bits.
+fn check_base32_len(secret: &str) -> Result<()> {
+    let len = secret.trim_end_matches('=').len();
+    if matches!(len % 8, 1 | 3 | 6) {
+        return Err(Error::SecretLength(len));
+    }
+    Ok(())
+}
+
+/// Check a base32 secret without decoding it and return the number
+/// of bytes it decodes to.
+///
+/// The characters accepted are the same as `decode_secret`;
+/// whitespace is ignored, lowercase is allowed and the secret may
+/// be padded.
+fn check_secret(secret: &str) -> Result<usize> {
+    let mut len = 0;
+    let mut padding = false;
+    for c in secret.chars().filter(|c| !c.is_ascii_whitespace()) {
+        match c.to_ascii_uppercase() {
+            '=' => padding = true,
+            'A'..='Z' | '2'..='7' if !padding => len += 1,
+            _ => return Err(Error::Secret(secret.to_string())),
         }
     }
+    if matches!(len % 8, 1 | 3 | 6) {
+        return Err(Error::SecretLength(len));
+    }
+    Ok(len * 5 / 8)
+}
+
+/// Decode a base32 secret.
+///
+/// ASCII whitespace is removed and the secret is converted to
+/// uppercase so that secrets copied from a provider, which are
+/// often grouped in fours (`KRSX G5CT MVRX`), can be decoded; padded
+/// secrets are also accepted.
+///
+/// Secrets whose length does not encode a whole number of bytes
+/// are rejected with [Error::SecretLength](enum.Error.html#variant.SecretLength).
+fn decode_secret(secret: &str) -> Result<Vec<u8>> {
+    check_secret(secret)?;
+    let normalized: String = secret
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    base32::decode(base32::Alphabet::Rfc4648 { padding: false }, &normalized)
+        .or_else(|| {
+            base32::decode(
+                base32::Alphabet::Rfc4648 { padding: true },
+                &normalized,
+            )
+        })
+        .ok_or_else(|| Error::Secret(secret.to_string()))
+}
+
+/// Split a field of `len` bytes from the front of an encoded TOTP.
+fn take_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if len > bytes.len() {
+        return Err(Error::Decode("unexpected end of data".to_string()));
+    }
+    let (value, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(value)
 }
 
+/// Split a length-prefixed field from the front of an encoded TOTP.
+fn take_prefixed<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let len = u32::from_be_bytes(take_bytes(bytes, 4)?.try_into().unwrap());
+    take_bytes(bytes, len as usize)
+}
+
+/// Split a length-prefixed UTF-8 field from the front of an encoded TOTP.
+fn take_string(bytes: &mut &[u8]) -> Result<String> {
+    String::from_utf8(take_prefixed(bytes)?.to_vec())
+        .map_err(|_| Error::Decode("string is not valid UTF-8".to_string()))
+}
+
+/// Append a length-prefixed field to an encoded TOTP.
+fn put_prefixed(bytes: &mut Vec<u8>, value: &[u8]) {
+    bytes.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(value);
+}
+
+/// Decode a hex secret.
+fn decode_hex(secret: &str) -> Result<Vec<u8>> {
+    if !secret.len().is_multiple_of(2)
+        || !secret.bytes().all(|b| b.is_ascii_hexdigit())
+    {
+        return Err(Error::Hex(secret.to_string()));
+    }
+    (0..secret.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&secret[i..i + 2], 16)
+                .map_err(|_| Error::Hex(secret.to_string()))
+        })
+        .collect()
+}
+
+/// Percent-encode a label component.
+fn url_encode(value: &str) -> String {
+    utf8_percent_encode(value, ENCODE_SET).to_string()
+}
+
+/// Percent-decode a label component.
+fn url_decode(value: &str) -> Option<String> {
+    percent_decode_str(value)
+        .decode_utf8()
+        .ok()
+        .map(|value| value.into_owned())
+}
+
+/// Normalize an account name or issuer to NFC so that composed
+/// and decomposed characters compare equal.
+#[cfg(feature = "normalize")]
+fn normalize(value: String) -> String {
+    use alloc::borrow::Cow;
+    use icu_normalizer::ComposingNormalizerBorrowed;
+    match ComposingNormalizerBorrowed::new_nfc().normalize(&value) {
+        Cow::Borrowed(_) => value,
+        Cow::Owned(normalized) => normalized,
+    }
+}
+
+/// Account names and issuers are kept as is without the
+/// `normalize` feature.
+#[cfg(not(feature = "normalize"))]
+fn normalize(value: String) -> String {
+    value
+}
+
+#[cfg(feature = "std")]
 fn system_time() -> Result<u64> {
     let t = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
     Ok(t)
 }
 
+/// Convert seconds since the Unix epoch of a date time into a
+/// timestamp, a date time before the epoch is an error the same
+/// as a system clock before the epoch.
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn unix_timestamp(seconds: i64) -> Result<u64> {
+    u64::try_from(seconds).map_err(|_| Error::Timestamp(seconds))
+}
+
+/// Parameters of an otpauth URL that must not be repeated.
+const UNIQUE_PARAMS: [&str; 4] = ["secret", "digits", "period", "algorithm"];
+
+/// Verify the scheme and host of an otpauth URL and that the
+/// parameters which change the tokens are not repeated.
+fn check_otpauth_url(url: &Url) -> Result<()> {
+    if url.scheme() != "otpauth" {
+        return Err(Error::Scheme(url.scheme().to_string()));
+    }
+    match url.host() {
+        Some(Host::Domain("totp")) | Some(Host::Domain("steam")) => {}
+        Some(host) => return Err(Error::Host(host.to_string())),
+        None => return Err(Error::Host("".to_string())),
+    }
+
+    let mut seen = [false; UNIQUE_PARAMS.len()];
+    for (key, _) in url.query_pairs() {
+        if let Some(i) = UNIQUE_PARAMS.iter().position(|param| *param == key)
+        {
+            if seen[i] {
+                return Err(Error::DuplicateParam(key.to_string()));
+            }
+            seen[i] = true;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the parameters of a TOTP the same as
+/// [TOTP::new](struct.TOTP.html#method.new) given the length of
+/// the secret in bytes, the secret is not checked when the length
+/// is `None`.
+fn check_params(
+    digits: usize,
+    skew: u8,
+    step: u64,
+    secret_len: Option<usize>,
+) -> Result<Digits> {
+    let digits = Digits::try_from(digits)?;
+
+    if skew > MAX_SKEW {
+        return Err(Error::SkewTooLarge(skew));
+    }
+
+    if step == 0 {
+        return Err(Error::InvalidStep(step));
+    }
+
+    if let Some(secret_len) = secret_len {
+        if secret_len < 16 {
+            return Err(Error::SecretTooSmall(secret_len * 8));
+        }
+    }
+
+    Ok(digits)
+}
+
+/// Check that the account name and issuer do not contain a colon
+/// which separates them in the label of an otpauth URL.
+fn check_label(account_name: &str, issuer: Option<&str>) -> Result<()> {
+    if account_name.contains(':') {
+        return Err(Error::AccountName(account_name.to_string()));
+    }
+
+    if let Some(issuer) = issuer {
+        if issuer.contains(':') {
+            return Err(Error::Issuer(issuer.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the secret of an otpauth URL without decoding it and
+/// return the length in bytes, the secret must not be missing.
+fn check_url_secret(secret: &str) -> Result<usize> {
+    let secret_len = check_secret(secret)?;
+    if secret_len == 0 {
+        return Err(Error::Secret("".to_string()));
+    }
+    Ok(secret_len)
+}
+
+/// Values of an otpauth URL before the secret is checked.
+struct UrlParts {
+    algorithm: Algorithm,
+    digits: usize,
+    step: u64,
+    t0: u64,
+    image: Option<String>,
+    secret: String,
+    account_name: String,
+    issuer: Option<String>,
+    steam: bool,
+    unsupported: Vec<(String, String)>,
+}
+
+/// Serialize the secret as a non-padded base32 string.
+#[cfg(feature = "serde")]
+mod serde_secret {
+    use alloc::{string::String, vec::Vec};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(
+        secret: &[u8],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&base32::encode(
+            base32::Alphabet::Rfc4648 { padding: false },
+            secret,
+        ))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let secret = String::deserialize(deserializer)?;
+        super::decode_secret(&secret).map_err(D::Error::custom)
+    }
+}
+
+/// Serialized representation of a TOTP.
+///
+/// A skew above [MAX_SKEW](constant.MAX_SKEW.html) and a colon in
+/// the label are marked as unchecked so they are accepted again
+/// when deserialized.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct TotpRef<'a> {
+    algorithm: Algorithm,
+    digits: Digits,
+    skew: u8,
+    asymmetric_skew: Option<Skew>,
+    step: u64,
+    t0: u64,
+    #[serde(serialize_with = "serde_secret::serialize")]
+    secret: &'a [u8],
+    account_name: &'a str,
+    issuer: Option<&'a str>,
+    image: Option<&'a str>,
+    steam: bool,
+    endianness: Endianness,
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    unchecked_skew: bool,
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    unchecked_label: bool,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for TOTP {
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        TotpRef {
+            algorithm: self.algorithm,
+            digits: self.digits,
+            skew: self.skew,
+            asymmetric_skew: self.asymmetric_skew,
+            step: self.step,
+            t0: self.t0,
+            secret: &self.secret,
+            account_name: &self.account_name,
+            issuer: self.issuer.as_deref(),
+            image: self.image.as_deref(),
+            steam: self.steam,
+            endianness: self.endianness,
+            unchecked_skew: self.is_skew_unchecked(),
+            unchecked_label: self.is_label_unchecked(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserialized representation of a TOTP that is validated
+/// by [TOTP::new](struct.TOTP.html#method.new).
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct TotpShadow {
+    algorithm: Algorithm,
+    digits: usize,
+    skew: u8,
+    #[serde(default)]
+    asymmetric_skew: Option<Skew>,
+    step: u64,
+    #[serde(default)]
+    t0: u64,
+    #[serde(with = "serde_secret")]
+    secret: Vec<u8>,
+    account_name: String,
+    issuer: Option<String>,
+    #[serde(default)]
+    image: Option<String>,
+    #[serde(default)]
+    steam: bool,
+    #[serde(default)]
+    endianness: Endianness,
+    #[serde(default)]
+    unchecked_skew: bool,
+    #[serde(default)]
+    unchecked_label: bool,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<TotpShadow> for TOTP {
+    type Error = Error;
+
+    fn try_from(value: TotpShadow) -> Result<Self> {
+        let new = if value.unchecked_label {
+            TOTP::new_unchecked_label
+        } else {
+            TOTP::new
+        };
+        // The skew is validated by with_decoded_skew.
+        let mut totp = new(
+            value.algorithm,
+            value.digits,
+            0,
+            value.step,
+            value.secret,
+            value.account_name,
+            value.issuer,
+        )?
+        .with_decoded_skew(
+            value.skew,
+            value.asymmetric_skew,
+            value.unchecked_skew,
+        )?;
+        totp.t0 = value.t0;
+        totp.image = value.image;
+        totp.steam = value.steam;
+        totp.endianness = value.endianness;
+        Ok(totp)
+    }
+}
+
+/// Generate the token for each TOTP given the provided timestamp in seconds.
+///
+/// Tokens are returned in the same order as the input.
+pub fn generate_multi(totps: &[TOTP], time: u64) -> Result<Vec<String>> {
+    totps.iter().map(|totp| totp.generate(time)).collect()
+}
+
+/// Verify the token against each TOTP given the provided timestamp
+/// in seconds and return the first TOTP that accepts it.
+///
+/// Every TOTP is verified even after a match so the time taken
+/// does not reveal which one matched.
+pub fn verify_any<'a>(
+    totps: impl IntoIterator<Item = &'a TOTP>,
+    token: &str,
+    time: u64,
+) -> Option<&'a TOTP> {
+    let mut matched = None;
+    for totp in totps {
+        if totp.verify(token, time) && matched.is_none() {
+            matched = Some(totp);
+        }
+    }
+    matched
+}
+
+/// Compare two tokens in constant time.
+///
+/// The time taken only depends on the length of the tokens, not on
+/// how many leading characters match, so it is safe to use when
+/// comparing a submitted token with one generated on the server.
+pub fn codes_equal(a: &str, b: &str) -> bool {
+    constant_time_eq(a.as_bytes(), b.as_bytes())
+}
+
 /// TOTP holds informations as to how to generate an auth code and validate it. Its [secret](struct.TOTP.html#structfield.secret) field is sensitive data, treat it accordingly
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "TotpShadow"))]
 #[cfg_attr(
     feature = "zeroize",
     derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop)
@@ -112,7 +910,8 @@ pub struct TOTP {
     ///
     /// Per [rfc-4226](https://tools.ietf.org/html/rfc4226#section-5.3), 
     /// this can be in the range between 6 and 8 digits
-    pub digits: usize,
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    pub digits: Digits,
 
     /// Number of steps allowed as network delay.
     ///
@@ -120,17 +919,32 @@ pub struct TOTP {
     /// one step after are valid.
     ///
     /// The recommended value per [rfc-6238](https://tools.ietf.org/html/rfc6238#section-5.2) is 1. Anything more is sketchy and should not be used.
+    ///
+    /// Values above [MAX_SKEW](constant.MAX_SKEW.html) are rejected unless
+    /// set with [with_skew_unchecked](struct.TOTP.html#method.with_skew_unchecked).
     pub skew: u8,
 
+    /// Number of steps allowed before and after the current step.
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    asymmetric_skew: Option<Skew>,
+
     /// Duration in seconds of a step.
     ///
     /// The recommended value per [rfc-6238](https://tools.ietf.org/html/rfc6238#section-5.2) is 30 seconds
     pub step: u64,
 
+    /// The Unix time in seconds to start counting steps from.
+    ///
+    /// The default value per [rfc-6238](https://tools.ietf.org/html/rfc6238#section-4.1) is 0;
+    /// timestamps before this time map to the first step.
+    pub t0: u64,
+
     /// As per [rfc-4226](https://tools.ietf.org/html/rfc4226#section-4) 
     /// the secret should come from a strong source, most likely a CSPRNG.
     ///
     /// It should be at least 128 bits, but 160 are recommended.
+    ///
+    /// When serialized the secret is a non-padded base32 string.
     pub secret: Vec<u8>,
 
     /// The account name, typically either an email address or username.
@@ -146,52 +960,148 @@ pub struct TOTP {
     ///
     /// Must not contain a colon `:`.
     pub issuer: Option<String>,
+
+    /// URL of an image (icon) for the account.
+    ///
+    /// Not part of the standard but set by many providers
+    /// in the `image` parameter of the otpauth URL.
+    pub image: Option<String>,
+
+    /// Whether tokens use the Steam Guard encoding.
+    steam: bool,
+
+    /// Byte order of the counter when it is signed.
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    endianness: Endianness,
 }
 
+/// Compares every field; the secret is compared in constant time.
+///
+/// Prior versions only compared the secret; use
+/// [same_secret](struct.TOTP.html#method.same_secret) for that behavior.
 impl PartialEq for TOTP {
     fn eq(&self, other: &Self) -> bool {
-        constant_time_eq(self.secret.as_ref(), other.secret.as_ref())
+        self.same_secret(other)
+            && self.algorithm == other.algorithm
+            && self.digits == other.digits
+            && self.skew == other.skew
+            && self.asymmetric_skew == other.asymmetric_skew
+            && self.step == other.step
+            && self.t0 == other.t0
+            && self.account_name == other.account_name
+            && self.issuer == other.issuer
+            && self.image == other.image
+            && self.steam == other.steam
+            && self.endianness == other.endianness
     }
 }
 
-impl TOTP {
-    /// Create a new instance of TOTP with given parameters.
-    ///
-    /// See [the doc](struct.TOTP.html#fields) for reference as to how to choose those values.
-    ///
-    /// * `digits`: MUST be between 6 & 8
-    /// * `secret`: Must have bitsize of at least 128
-    /// * `account_name`: Must not contain `:`
-    /// * `issuer`: Must not contain `:`
-    ///
-    pub fn new(
-        algorithm: Algorithm,
-        digits: usize,
-        skew: u8,
-        step: u64,
-        secret: Vec<u8>,
-        account_name: String,
-        issuer: Option<String>,
-    ) -> Result<TOTP> {
-        if !(6..=8).contains(&digits) {
-            return Err(Error::InvalidDigits(digits));
-        }
+impl Eq for TOTP {}
 
-        if secret.len() < 16 {
-            return Err(Error::SecretTooSmall(secret.len() * 8));
-        }
+/// Hashes every field compared by [PartialEq](#impl-PartialEq-for-TOTP)
+/// so that equal TOTPs have the same hash.
+impl Hash for TOTP {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.algorithm.hash(state);
+        self.digits.hash(state);
+        self.skew.hash(state);
+        self.asymmetric_skew.hash(state);
+        self.step.hash(state);
+        self.t0.hash(state);
+        self.secret.hash(state);
+        self.account_name.hash(state);
+        self.issuer.hash(state);
+        self.image.hash(state);
+        self.steam.hash(state);
+        self.endianness.hash(state);
+    }
+}
 
-        if account_name.contains(':') {
-            return Err(Error::AccountName(account_name));
-        }
+impl fmt::Debug for TOTP {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TOTP")
+            .field("algorithm", &self.algorithm)
+            .field("digits", &self.digits)
+            .field("skew", &self.skew)
+            .field("asymmetric_skew", &self.asymmetric_skew)
+            .field("step", &self.step)
+            .field("t0", &self.t0)
+            .field("secret", &format_args!("[redacted]"))
+            .field("account_name", &self.account_name)
+            .field("issuer", &self.issuer)
+            .field("image", &self.image)
+            .field("steam", &self.steam)
+            .field("endianness", &self.endianness)
+            .finish()
+    }
+}
 
-        if let Some(issuer) = &issuer {
-            if issuer.contains(':') {
-                return Err(Error::Issuer(issuer.to_string()));
-            }
-        }
+impl fmt::Display for TOTP {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.get_url())
+    }
+}
 
-        Ok(TOTP {
+impl FromStr for TOTP {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        TOTP::from_url(s)
+    }
+}
+
+impl TryFrom<&str> for TOTP {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        TOTP::from_url(value)
+    }
+}
+
+impl TryFrom<String> for TOTP {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        TOTP::from_url(value)
+    }
+}
+
+impl TryFrom<Url> for TOTP {
+    type Error = Error;
+
+    /// Generate a TOTP from an already parsed otpauth URL.
+    ///
+    /// The skew is 0, the same as [from_url](struct.TOTP.html#method.from_url).
+    fn try_from(url: Url) -> Result<Self> {
+        Ok(TOTP::from_parsed_url(url, 0, true)?.0)
+    }
+}
+
+impl TOTP {
+    /// Create a new instance of TOTP with given parameters.
+    ///
+    /// See [the doc](struct.TOTP.html#fields) for reference as to how to choose those values.
+    ///
+    /// * `digits`: MUST be between 6 & 8
+    /// * `skew`: Must not be more than [MAX_SKEW](constant.MAX_SKEW.html)
+    /// * `step`: Must not be 0
+    /// * `secret`: Must have bitsize of at least 128, a `Vec<u8>`, byte
+    ///   slice or array is accepted
+    /// * `account_name`: Must not contain `:`
+    /// * `issuer`: Must not contain `:`
+    ///
+    /// With the `normalize` feature the `account_name` and `issuer`
+    /// are normalized to Unicode NFC.
+    pub fn new(
+        algorithm: Algorithm,
+        digits: usize,
+        skew: u8,
+        step: u64,
+        secret: impl Into<Vec<u8>>,
+        account_name: String,
+        issuer: Option<String>,
+    ) -> Result<TOTP> {
+        let totp = TOTP::new_unchecked_label(
             algorithm,
             digits,
             skew,
@@ -199,679 +1109,4973 @@ impl TOTP {
             secret,
             account_name,
             issuer,
+        )?;
+        check_label(&totp.account_name, totp.issuer.as_deref())?;
+        Ok(totp)
+    }
+
+    /// Create a new instance of TOTP with given parameters allowing
+    /// `:` in the `account_name` and `issuer`.
+    ///
+    /// The other parameters are validated the same as [new](struct.TOTP.html#method.new).
+    /// [get_url](struct.TOTP.html#method.get_url) percent-encodes the
+    /// colons as `%3A` so the separator of the label stays unambiguous;
+    /// read the URL back with [from_url_unchecked_label](struct.TOTP.html#method.from_url_unchecked_label)
+    /// as the other URL parsers still reject the colons.
+    /// [to_bytes](struct.TOTP.html#method.to_bytes),
+    /// [to_env_string](struct.TOTP.html#method.to_env_string) and serde
+    /// mark the label as unchecked so it is accepted again when decoded.
+    pub fn new_unchecked_label(
+        algorithm: Algorithm,
+        digits: usize,
+        skew: u8,
+        step: u64,
+        secret: impl Into<Vec<u8>>,
+        account_name: String,
+        issuer: Option<String>,
+    ) -> Result<TOTP> {
+        let secret = secret.into();
+        let digits = check_params(digits, skew, step, Some(secret.len()))?;
+
+        Ok(TOTP {
+            algorithm,
+            digits,
+            skew,
+            asymmetric_skew: None,
+            step,
+            t0: 0,
+            secret,
+            account_name: normalize(account_name),
+            issuer: issuer.map(normalize),
+            image: None,
+            steam: false,
+            endianness: Endianness::Big,
         })
     }
 
-    /// Sign the given timestamp
-    pub fn sign(&self, time: u64) -> Vec<u8> {
-        self.algorithm.sign(
-            self.secret.as_ref(),
-            (time / self.step).to_be_bytes().as_ref(),
-        )
+    /// Create a new instance of TOTP with given parameters enforcing
+    /// the recommended secret length for the algorithm.
+    ///
+    /// The secret must be at least as long as the HMAC output of the
+    /// algorithm; 160 bits for SHA1, 256 bits for SHA256 and 512 bits
+    /// for SHA512. Otherwise the validation is the same as
+    /// [new](struct.TOTP.html#method.new).
+    pub fn new_strict(
+        algorithm: Algorithm,
+        digits: usize,
+        skew: u8,
+        step: u64,
+        secret: impl Into<Vec<u8>>,
+        account_name: String,
+        issuer: Option<String>,
+    ) -> Result<TOTP> {
+        let secret = secret.into();
+        if secret.len() < algorithm.recommended_secret_len() {
+            return Err(Error::SecretTooSmall(secret.len() * 8));
+        }
+        TOTP::new(algorithm, digits, skew, step, secret, account_name, issuer)
     }
 
-    /// Generate a token given the provided timestamp in seconds
-    pub fn generate(&self, time: u64) -> String {
-        let result: &[u8] = &self.sign(time);
-        let offset = (result.last().unwrap() & 15) as usize;
-        let result = u32::from_be_bytes(
-            result[offset..offset + 4].try_into().unwrap(),
-        ) & 0x7fff_ffff;
-        format!(
-            "{1:00$}",
-            self.digits,
-            result % 10_u32.pow(self.digits as u32)
+    /// Create a new instance of TOTP using the settings supported by
+    /// most authenticator apps; SHA1, 6 digits, a skew of 1 and a
+    /// step of 30 seconds.
+    ///
+    /// The validation is the same as [new](struct.TOTP.html#method.new).
+    pub fn new_default(
+        secret: impl Into<Vec<u8>>,
+        account_name: String,
+        issuer: Option<String>,
+    ) -> Result<TOTP> {
+        TOTP::new(Algorithm::SHA1, 6, 1, 30, secret, account_name, issuer)
+    }
+
+    /// Create a new instance from the parameters of a [TotpConfig](struct.TotpConfig.html).
+    ///
+    /// The validation is the same as [new](struct.TOTP.html#method.new).
+    pub fn from_config(
+        config: TotpConfig,
+        secret: impl Into<Vec<u8>>,
+        account_name: String,
+        issuer: Option<String>,
+    ) -> Result<TOTP> {
+        TOTP::new(
+            config.algorithm,
+            config.digits.get(),
+            config.skew,
+            config.step,
+            secret,
+            account_name,
+            issuer,
         )
     }
 
-    /// Returns the timestamp of the first second for the next step
-    /// given the provided timestamp in seconds
-    pub fn next_step(&self, time: u64) -> u64 {
-        let step = time / self.step;
-        (step + 1) * self.step
+    /// The parameters without the secret or the label.
+    pub fn config(&self) -> TotpConfig {
+        TotpConfig {
+            algorithm: self.algorithm,
+            digits: self.digits,
+            skew: self.skew,
+            step: self.step,
+        }
     }
 
-    /// Returns the timestamp of the first second of the next step
-    /// According to system time
-    pub fn next_step_current(&self) -> Result<u64> {
-        let t = system_time()?;
-        Ok(self.next_step(t))
+    /// Change the [account_name](struct.TOTP.html#structfield.account_name)
+    /// validating it the same as [new](struct.TOTP.html#method.new).
+    ///
+    /// Prefer this to setting the field directly which bypasses the
+    /// check that the account name does not contain a colon.
+    pub fn set_account_name(&mut self, account_name: String) -> Result<()> {
+        let account_name = normalize(account_name);
+        if account_name.contains(':') {
+            return Err(Error::AccountName(account_name));
+        }
+        self.account_name = account_name;
+        Ok(())
     }
 
-    /// Give the ttl (in seconds) of the current token
-    pub fn ttl(&self) -> Result<u64> {
-        let t = system_time()?;
-        Ok(self.step - (t % self.step))
+    /// Change the [step](struct.TOTP.html#structfield.step) validating
+    /// it the same as [new](struct.TOTP.html#method.new).
+    ///
+    /// Prefer this to setting the field directly which bypasses the
+    /// check that the step is not 0.
+    pub fn with_step(self, step: u64) -> Result<TOTP> {
+        if step == 0 {
+            return Err(Error::InvalidStep(step));
+        }
+        let mut totp = self;
+        totp.step = step;
+        Ok(totp)
     }
 
-    /// Generate a token from the current system time
-    pub fn generate_current(&self) -> Result<String> {
-        let t = system_time()?;
-        Ok(self.generate(t))
+    /// Change the [digits](struct.TOTP.html#structfield.digits) validating
+    /// them the same as [new](struct.TOTP.html#method.new).
+    pub fn with_digits(self, digits: usize) -> Result<TOTP> {
+        let mut totp = self;
+        totp.digits = Digits::try_from(digits)?;
+        Ok(totp)
     }
 
-    /// Check if token is valid given the provided timestamp 
-    /// in seconds, accounting [skew](struct.TOTP.html#structfield.skew)
-    pub fn check(&self, token: &str, time: u64) -> bool {
-        let basestep = time / self.step - (self.skew as u64);
-        for i in 0..self.skew * 2 + 1 {
-            let step_time = (basestep + (i as u64)) * (self.step as u64);
-
-            if constant_time_eq(
-                self.generate(step_time).as_bytes(),
-                token.as_bytes(),
-            ) {
-                return true;
-            }
-        }
-        false
+    /// Copy with a different [algorithm](struct.TOTP.html#structfield.algorithm),
+    /// everything else including the secret is preserved.
+    ///
+    /// The algorithm does not affect the other invariants so there is
+    /// nothing to validate; combined with [get_url](struct.TOTP.html#method.get_url)
+    /// this gives the provisioning URL to migrate an account to a
+    /// stronger algorithm.
+    pub fn with_algorithm(&self, algorithm: Algorithm) -> TOTP {
+        let mut totp = self.clone();
+        totp.algorithm = algorithm;
+        totp
     }
 
-    /// Check if token is valid by current system time, 
-    /// accounting [skew](struct.TOTP.html#structfield.skew).
-    pub fn check_current(&self, token: &str) -> Result<bool> {
-        let t = system_time()?;
-        Ok(self.check(token, t))
+    /// Whether tokens use the Steam Guard encoding.
+    ///
+    /// When enabled [generate](struct.TOTP.html#method.generate) and
+    /// [verify](struct.TOTP.html#method.verify) use five alphanumeric
+    /// characters the same as [generate_steam](struct.TOTP.html#method.generate_steam)
+    /// and the [digits](struct.TOTP.html#structfield.digits) are ignored.
+    pub fn is_steam(&self) -> bool {
+        self.steam
     }
 
-    /// Return the base32 representation of the secret, which 
-    /// might be useful when users want to manually add the 
-    /// secret to their authenticator.
-    pub fn to_secret_base32(&self) -> String {
-        base32::encode(
-            base32::Alphabet::RFC4648 { padding: false },
-            self.secret.as_ref(),
-        )
+    /// Change whether tokens use the Steam Guard encoding.
+    pub fn with_steam(self, steam: bool) -> TOTP {
+        let mut totp = self;
+        totp.steam = steam;
+        totp
     }
 
-    /// Convert a base32 secret into a TOTP.
+    /// Byte order of the counter when it is signed, big-endian
+    /// unless changed with [with_endianness](struct.TOTP.html#method.with_endianness).
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// Change the byte order of the counter when it is signed.
     ///
-    /// The account name is the empty string and the issuer is None; 
-    /// so you should set them explicitly after decoding the secret bytes.
-    pub fn from_secret_base32<S: AsRef<str>>(secret: S) -> Result<TOTP> {
-        let buffer = base32::decode(
-            base32::Alphabet::RFC4648 { padding: false },
-            secret.as_ref(),
-        )
-        .ok_or(Error::Secret(secret.as_ref().to_string()))?;
+    /// Big-endian is the only choice compliant with
+    /// [rfc-4226](https://tools.ietf.org/html/rfc4226#section-5.2);
+    /// [Endianness::Little](enum.Endianness.html#variant.Little) only
+    /// exists to test against hardware tokens that sign the counter
+    /// in the wrong order and must not be used otherwise. The byte
+    /// order is not part of the otpauth URL.
+    pub fn with_endianness(self, endianness: Endianness) -> TOTP {
+        let mut totp = self;
+        totp.endianness = endianness;
+        totp
+    }
 
-        TOTP::new(Algorithm::SHA1, 6, 1, 30, buffer, String::new(), None)
+    /// Bytes of the counter that are signed.
+    fn counter_bytes(&self, counter: u64) -> [u8; 8] {
+        match self.endianness {
+            Endianness::Big => counter.to_be_bytes(),
+            Endianness::Little => counter.to_le_bytes(),
+        }
     }
 
-    /// Generate a TOTP from the standard otpauth URL
-    pub fn from_url<S: AsRef<str>>(url: S) -> Result<TOTP> {
-        let url = Url::parse(url.as_ref())?;
+    /// Change the [skew](struct.TOTP.html#structfield.skew) validating
+    /// it the same as [new](struct.TOTP.html#method.new).
+    pub fn with_skew(self, skew: u8) -> Result<TOTP> {
+        if skew > MAX_SKEW {
+            return Err(Error::SkewTooLarge(skew));
+        }
+        Ok(self.with_skew_unchecked(skew))
+    }
 
-        if url.scheme() != "otpauth" {
-            return Err(Error::Scheme(url.scheme().to_string()));
+    /// Change the [skew](struct.TOTP.html#structfield.skew) allowing
+    /// values above [MAX_SKEW](constant.MAX_SKEW.html).
+    ///
+    /// Every step within the skew is signed when a token is verified
+    /// and a wider window accepts more tokens so only use this when
+    /// the clocks of the clients are known to drift that much.
+    pub fn with_skew_unchecked(self, skew: u8) -> TOTP {
+        let mut totp = self;
+        totp.skew = skew;
+        totp
+    }
+
+    /// Number of steps accepted before and after the current step
+    /// when it differs in each direction.
+    ///
+    /// When set this is used instead of [skew](struct.TOTP.html#structfield.skew).
+    pub fn asymmetric_skew(&self) -> Option<Skew> {
+        self.asymmetric_skew
+    }
+
+    /// Change the [asymmetric skew](struct.TOTP.html#method.asymmetric_skew),
+    /// `None` to use the [skew](struct.TOTP.html#structfield.skew) in
+    /// both directions.
+    ///
+    /// Each direction is limited to [MAX_SKEW](constant.MAX_SKEW.html)
+    /// the same as the skew.
+    pub fn with_asymmetric_skew(self, skew: Option<Skew>) -> Result<TOTP> {
+        if let Some(skew) = skew {
+            let largest = skew.before.max(skew.after);
+            if largest > MAX_SKEW {
+                return Err(Error::SkewTooLarge(largest));
+            }
         }
-        if url.host() != Some(Host::Domain("totp")) {
-            return Err(Error::Host(url.host().unwrap().to_string()));
+        Ok(self.with_asymmetric_skew_unchecked(skew))
+    }
+
+    /// Change the [asymmetric skew](struct.TOTP.html#method.asymmetric_skew)
+    /// allowing values above [MAX_SKEW](constant.MAX_SKEW.html) like
+    /// [with_skew_unchecked](struct.TOTP.html#method.with_skew_unchecked).
+    pub fn with_asymmetric_skew_unchecked(self, skew: Option<Skew>) -> TOTP {
+        let mut totp = self;
+        totp.asymmetric_skew = skew;
+        totp
+    }
+
+    /// Whether a skew is above [MAX_SKEW](constant.MAX_SKEW.html) so
+    /// it must be marked as unchecked when encoded.
+    fn is_skew_unchecked(&self) -> bool {
+        self.skew > MAX_SKEW
+            || self
+                .asymmetric_skew
+                .is_some_and(|skew| skew.before.max(skew.after) > MAX_SKEW)
+    }
+
+    /// Whether the label contains a colon, only allowed by
+    /// [new_unchecked_label](struct.TOTP.html#method.new_unchecked_label),
+    /// so it must be marked as unchecked when encoded.
+    fn is_label_unchecked(&self) -> bool {
+        self.account_name.contains(':')
+            || self
+                .issuer
+                .as_ref()
+                .is_some_and(|issuer| issuer.contains(':'))
+    }
+
+    /// Set the skews read by a decoder, validated unless the encoded
+    /// TOTP was marked as unchecked.
+    fn with_decoded_skew(
+        self,
+        skew: u8,
+        asymmetric_skew: Option<Skew>,
+        unchecked: bool,
+    ) -> Result<TOTP> {
+        if unchecked {
+            Ok(self
+                .with_skew_unchecked(skew)
+                .with_asymmetric_skew_unchecked(asymmetric_skew))
+        } else {
+            self.with_skew(skew)?.with_asymmetric_skew(asymmetric_skew)
         }
+    }
 
-        let mut algorithm = Algorithm::SHA1;
-        let mut digits = 6;
-        let mut step = 30;
-        let mut secret = Vec::new();
-        let mut account_name: String;
-        let mut issuer: Option<String> = None;
+    /// Returns the counter (step number) for the provided
+    /// timestamp in seconds, or zero if the
+    /// [step](struct.TOTP.html#structfield.step) is zero.
+    pub fn counter(&self, time: u64) -> u64 {
+        self.checked_counter(time).unwrap_or(0)
+    }
 
-        let path = url.path().trim_start_matches('/');
-        if path.contains(':') {
-            let parts = path.split_once(':').unwrap();
-            issuer = Some(
-                urlencoding::decode(parts.0.to_owned().as_str())
-                    .map_err(|_| Error::IssuerDecoding(parts.0.to_owned()))?
-                    .to_string(),
-            );
-            account_name = parts.1.trim_start_matches(':').to_owned();
+    /// The counter for the provided timestamp in seconds; a step of
+    /// zero, which can only be set through the public field, is an
+    /// error so no token is generated or verified.
+    fn checked_counter(&self, time: u64) -> Result<u64> {
+        time.saturating_sub(self.t0)
+            .checked_div(self.step)
+            .ok_or(Error::InvalidStep(self.step))
+    }
+
+    /// Timestamp of the first second of the step for a counter.
+    ///
+    /// Saturates at `u64::MAX` as the [t0](struct.TOTP.html#structfield.t0)
+    /// from a URL is not bounded.
+    fn step_time(&self, counter: u64) -> u64 {
+        self.t0.saturating_add(counter.saturating_mul(self.step))
+    }
+
+    /// Returns the counter (step number) according to system time
+    #[cfg(feature = "std")]
+    pub fn counter_current(&self) -> Result<u64> {
+        let t = system_time()?;
+        Ok(self.counter(t))
+    }
+
+    /// Length of the secret in bits.
+    pub fn secret_bits(&self) -> usize {
+        self.secret.len() * 8
+    }
+
+    /// Strength of the secret for the [algorithm](struct.TOTP.html#structfield.algorithm).
+    ///
+    /// This is advisory, for example to warn users when provisioning,
+    /// use [new_strict](struct.TOTP.html#method.new_strict) to enforce
+    /// the recommended length.
+    pub fn secret_strength(&self) -> SecretStrength {
+        if self.secret.len() >= self.algorithm.recommended_secret_len() {
+            SecretStrength::Recommended
+        } else if self.secret.len() >= 16 {
+            SecretStrength::Minimum
         } else {
-            account_name = path.to_owned();
+            SecretStrength::BelowMinimum
         }
+    }
+
+    /// Compare only the secret of two TOTPs in constant time.
+    pub fn same_secret(&self, other: &TOTP) -> bool {
+        constant_time_eq(self.secret.as_ref(), other.secret.as_ref())
+    }
 
-        account_name = urlencoding::decode(account_name.as_str())
-            .map_err(|_| Error::AccountName(account_name.to_string()))?
-            .to_string();
+    /// Compare the values that appear in the provisioning URL created
+    /// by [get_url](struct.TOTP.html#method.get_url), for example to
+    /// decide whether two imports are the same account. Steam mode is
+    /// compared too because the URL records it as `encoder=steam`.
+    ///
+    /// The secret is compared in constant time; the skew, which is not
+    /// part of the URL, is ignored unlike [PartialEq](#impl-PartialEq-for-TOTP).
+    pub fn same_provisioning(&self, other: &TOTP) -> bool {
+        self.same_secret(other)
+            && self.algorithm == other.algorithm
+            && self.digits == other.digits
+            && self.step == other.step
+            && self.t0 == other.t0
+            && self.account_name == other.account_name
+            && self.issuer == other.issuer
+            && self.image == other.image
+            && self.steam == other.steam
+    }
 
-        for (key, value) in url.query_pairs() {
-            match key.as_ref() {
-                "algorithm" => {
-                    algorithm = match value.as_ref() {
-                        "SHA1" => Algorithm::SHA1,
-                        "SHA256" => Algorithm::SHA256,
-                        "SHA512" => Algorithm::SHA512,
-                        _ => return Err(Error::Algorithm(value.to_string())),
-                    }
-                }
-                "digits" => {
-                    digits = value
-                        .parse::<usize>()
-                        .map_err(|_| Error::Digits(value.to_string()))?;
-                }
-                "period" => {
-                    step = value
-                        .parse::<u64>()
-                        .map_err(|_| Error::Step(value.to_string()))?;
-                }
-                "secret" => {
-                    secret = base32::decode(
-                        base32::Alphabet::RFC4648 { padding: false },
-                        value.as_ref(),
-                    )
-                    .ok_or_else(|| Error::Secret(value.to_string()))?;
-                }
-                "issuer" => {
-                    let param_issuer = value
-                        .parse::<String>()
-                        .map_err(|_| Error::Issuer(value.to_string()))?;
-                    if issuer.is_some()
-                        && param_issuer.as_str() != issuer.as_ref().unwrap()
-                    {
-                        return Err(Error::IssuerMismatch(
-                            issuer.as_ref().unwrap().to_string(),
-                            param_issuer,
-                        ));
-                    }
-                    issuer = Some(param_issuer);
-                }
-                _ => {}
+    /// Sign the given timestamp
+    ///
+    /// Returns [Error::Hmac](enum.Error.html#variant.Hmac) if the
+    /// secret cannot be used as an HMAC key; this is a breaking
+    /// change from earlier versions which would panic instead.
+    ///
+    /// The signature is returned as a [Secret](struct.Secret.html)
+    /// which is cleared when dropped if the `zeroize` feature is
+    /// enabled.
+    pub fn sign(&self, time: u64) -> Result<Secret> {
+        self.sign_counter(self.checked_counter(time)?)
+    }
+
+    /// Sign the given counter
+    fn sign_counter(&self, counter: u64) -> Result<Secret> {
+        self.algorithm
+            .sign(self.secret.as_ref(), &self.counter_bytes(counter))
+    }
+
+    /// Dynamic truncation of the signature for the given counter,
+    /// the signature is not copied so nothing is allocated.
+    fn truncated_counter(&self, counter: u64) -> Result<u32> {
+        self.algorithm.sign_truncated(
+            self.secret.as_ref(),
+            &self.counter_bytes(counter),
+        )
+    }
+
+    /// Dynamic truncation of the signature for the given timestamp
+    /// as per [rfc-4226](https://tools.ietf.org/html/rfc4226#section-5.3).
+    ///
+    /// This is the 31-bit value before it is reduced to
+    /// [digits](struct.TOTP.html#structfield.digits) and zero-padded
+    /// by [generate](struct.TOTP.html#method.generate), useful for
+    /// alternative encodings of the token. Unlike
+    /// [sign](struct.TOTP.html#method.sign) it does not allocate.
+    pub fn truncated(&self, time: u64) -> Result<u32> {
+        self.truncated_counter(self.checked_counter(time)?)
+    }
+
+    /// Format a truncated value as a decimal token
+    fn format_token(&self, result: u32) -> String {
+        format!("{1:00$}", self.digits.get(), result % self.digits.modulus())
+    }
+
+    /// Generate a token given the provided timestamp in seconds
+    ///
+    /// In [Steam mode](struct.TOTP.html#method.is_steam) this is
+    /// the same as [generate_steam](struct.TOTP.html#method.generate_steam).
+    pub fn generate(&self, time: u64) -> Result<String> {
+        Ok(self.encode_token(self.truncated(time)?))
+    }
+
+    /// Generate a token given the provided timestamp in seconds and
+    /// write it to the start of the buffer as ASCII, returning the
+    /// number of bytes written.
+    ///
+    /// Nothing is allocated which makes it suitable for embedded
+    /// devices and hot paths. Returns [Error::BufferTooSmall](enum.Error.html#variant.BufferTooSmall)
+    /// if the buffer is shorter than the [digits](struct.TOTP.html#structfield.digits),
+    /// or five bytes in [Steam mode](struct.TOTP.html#method.is_steam).
+    pub fn generate_into(&self, time: u64, out: &mut [u8]) -> Result<usize> {
+        let len = if self.steam {
+            STEAM_DIGITS
+        } else {
+            self.digits.get()
+        };
+        let out = out.get_mut(..len).ok_or(Error::BufferTooSmall(len))?;
+        let result = self.truncated(time)?;
+        if self.steam {
+            let mut result = result as usize;
+            for byte in out.iter_mut() {
+                *byte = STEAM_CHARS[result % STEAM_CHARS.len()];
+                result /= STEAM_CHARS.len();
+            }
+        } else {
+            let mut result = result % self.digits.modulus();
+            for byte in out.iter_mut().rev() {
+                *byte = b'0' + (result % 10) as u8;
+                result /= 10;
             }
         }
+        Ok(len)
+    }
 
-        if secret.is_empty() {
-            return Err(Error::Secret("".to_string()));
+    /// Encode a truncated value as a token, in
+    /// [Steam mode](struct.TOTP.html#method.is_steam) this is a
+    /// Steam Guard token otherwise a decimal token.
+    fn encode_token(&self, result: u32) -> String {
+        if self.steam {
+            steam_token(result)
+        } else {
+            self.format_token(result)
         }
+    }
 
-        TOTP::new(algorithm, digits, 1, step, secret, account_name, issuer)
+    /// Generate a token as an integer given the provided timestamp
+    /// in seconds.
+    ///
+    /// This is the value before it is zero-padded to
+    /// [digits](struct.TOTP.html#structfield.digits) so the digits are
+    /// needed to display it, for example `format!("{:01$}", code, digits)`.
+    /// Steam mode is ignored.
+    pub fn generate_numeric(&self, time: u64) -> Result<u32> {
+        Ok(self.truncated(time)? % self.digits.modulus())
     }
 
-    /// Generate a standard URL used to automatically add TOTP auths.
+    /// Generate a token for the given counter (step number).
     ///
-    /// Usually used with a QR code.
+    /// The counter is signed directly without converting a timestamp
+    /// using the [step](struct.TOTP.html#structfield.step) and
+    /// [t0](struct.TOTP.html#structfield.t0) which makes it possible to
+    /// reproduce HOTP values such as the
+    /// [rfc-4226](https://tools.ietf.org/html/rfc4226#appendix-D) test vectors.
     ///
-    /// Label and issuer will be URL-encoded; the secret will be 
-    /// converted to base32 without padding, as per the RFC.
-    pub fn get_url(&self) -> String {
-        let account_name: String =
-            urlencoding::encode(self.account_name.as_str()).to_string();
-        let mut label: String = format!("{}?", account_name);
-        if self.issuer.is_some() {
-            let issuer: String =
-                urlencoding::encode(self.issuer.as_ref().unwrap().as_str())
-                    .to_string();
-            label = format!("{0}:{1}?issuer={0}&", issuer, account_name);
+    /// In [Steam mode](struct.TOTP.html#method.is_steam) the token is
+    /// a Steam Guard token the same as [generate](struct.TOTP.html#method.generate).
+    pub fn generate_at_counter(&self, counter: u64) -> Result<String> {
+        Ok(self.encode_token(self.truncated_counter(counter)?))
+    }
+
+    /// Generate a token and an RGB color given the provided
+    /// timestamp in seconds.
+    ///
+    /// The color is derived from the whole signature rather than
+    /// the token so that it carries the full entropy of the HMAC.
+    /// Devices sharing the same secret display the same color
+    /// which helps users visually confirm a token. The token is the
+    /// same as [generate](struct.TOTP.html#method.generate), including
+    /// in [Steam mode](struct.TOTP.html#method.is_steam).
+    pub fn generate_with_color(
+        &self,
+        time: u64,
+    ) -> Result<(String, [u8; 3])> {
+        let signature = self.sign(time)?;
+        let mut color = [0u8; 3];
+        for (i, byte) in signature.iter().enumerate() {
+            color[i % 3] ^= byte;
         }
+        Ok((self.encode_token(truncate(&signature)), color))
+    }
 
-        format!(
-            "otpauth://totp/{}secret={}&digits={}&algorithm={}",
-            label,
-            self.to_secret_base32(),
+    /// Generate a Steam Guard token given the provided timestamp in seconds.
+    ///
+    /// Steam uses the same truncated value as a standard token but
+    /// encodes it as five alphanumeric characters; the
+    /// [digits](struct.TOTP.html#structfield.digits) field is ignored.
+    pub fn generate_steam(&self, time: u64) -> Result<String> {
+        Ok(steam_token(self.truncated(time)?))
+    }
+
+    /// Iterate the upcoming tokens starting with the step that contains
+    /// the provided timestamp in seconds.
+    ///
+    /// Each item is the timestamp of the first second of the step
+    /// and the token for that step. The iterator ends after the last
+    /// step that the counter can represent.
+    pub fn codes_from(
+        &self,
+        start_time: u64,
+    ) -> impl Iterator<Item = Result<(u64, String)>> + '_ {
+        (self.counter(start_time)..=u64::MAX).map(move |counter| {
+            let time = self.step_time(counter);
+            Ok((time, self.generate(time)?))
+        })
+    }
+
+    /// Returns the timestamp of the first second for the next step
+    /// given the provided timestamp in seconds
+    pub fn next_step(&self, time: u64) -> u64 {
+        self.step_time(self.counter(time).saturating_add(1))
+    }
+
+    /// Returns the timestamp of the first second of the next step
+    /// According to system time
+    #[cfg(feature = "std")]
+    pub fn next_step_current(&self) -> Result<u64> {
+        let t = system_time()?;
+        Ok(self.next_step(t))
+    }
+
+    /// Returns the timestamps of the first second of the step for the
+    /// provided timestamp in seconds and of the first second of the
+    /// next step.
+    pub fn step_bounds(&self, time: u64) -> (u64, u64) {
+        let counter = self.counter(time);
+        (
+            self.step_time(counter),
+            self.step_time(counter.saturating_add(1)),
+        )
+    }
+
+    /// Returns the timestamps of the first second of the current step
+    /// and of the next step according to system time.
+    ///
+    /// The system time is read once so both bounds belong to the
+    /// same step.
+    #[cfg(feature = "std")]
+    pub fn current_step_bounds(&self) -> Result<(u64, u64)> {
+        let t = system_time()?;
+        Ok(self.step_bounds(t))
+    }
+
+    /// Give the ttl (in seconds) of the current token
+    #[cfg(feature = "std")]
+    pub fn ttl(&self) -> Result<u64> {
+        let t = system_time()?;
+        Ok(self.ttl_at(t))
+    }
+
+    /// Give the ttl (in seconds) of the token for the provided
+    /// timestamp in seconds.
+    ///
+    /// This is the time until [next_step](struct.TOTP.html#method.next_step)
+    /// so it is always between 1 and the [step](struct.TOTP.html#structfield.step);
+    /// on a step boundary a new token starts and the ttl is the whole
+    /// step, never 0. Timestamps before [t0](struct.TOTP.html#structfield.t0)
+    /// belong to the first step which ends at `t0 + step`.
+    pub fn ttl_at(&self, time: u64) -> u64 {
+        self.next_step(time).saturating_sub(time)
+    }
+
+    /// Generate a token from the current system time
+    #[cfg(feature = "std")]
+    pub fn generate_current(&self) -> Result<String> {
+        let t = system_time()?;
+        self.generate(t)
+    }
+
+    /// Generate a token for a `chrono` date time.
+    ///
+    /// Returns [Error::Timestamp](enum.Error.html#variant.Timestamp)
+    /// for a date time before the Unix epoch.
+    #[cfg(feature = "chrono")]
+    pub fn generate_at(&self, time: DateTime<Utc>) -> Result<String> {
+        self.generate(unix_timestamp(time.timestamp())?)
+    }
+
+    /// Generate a token for a `time` date time.
+    ///
+    /// Returns [Error::Timestamp](enum.Error.html#variant.Timestamp)
+    /// for a date time before the Unix epoch.
+    #[cfg(feature = "time")]
+    pub fn generate_at_offset(&self, time: OffsetDateTime) -> Result<String> {
+        self.generate(unix_timestamp(time.unix_timestamp())?)
+    }
+
+    /// Generate a token from the current system time treating a
+    /// system clock set before the Unix epoch as time 0.
+    ///
+    /// **Warning**: this masks a misconfigured clock; the token
+    /// generated for time 0 will not be accepted by a server with a
+    /// correct clock and no error is reported to help diagnose why.
+    /// Prefer [generate_current](struct.TOTP.html#method.generate_current)
+    /// unless that degenerate behavior is acceptable.
+    ///
+    /// The only error returned is
+    /// [Error::Hmac](enum.Error.html#variant.Hmac) from signing.
+    #[cfg(feature = "std")]
+    pub fn generate_at_system_time_or_zero(&self) -> Result<String> {
+        let t = system_time().unwrap_or(0);
+        self.generate(t)
+    }
+
+    /// Generate a token and give its ttl (in seconds) from the
+    /// current system time.
+    ///
+    /// The system time is read once so the ttl always belongs
+    /// to the returned token.
+    #[cfg(feature = "std")]
+    pub fn generate_current_with_ttl(&self) -> Result<(String, u64)> {
+        let t = system_time()?;
+        Ok((self.generate(t)?, self.ttl_at(t)))
+    }
+
+    /// Check if token is valid given the provided timestamp 
+    /// in seconds, accounting [skew](struct.TOTP.html#structfield.skew)
+    #[deprecated(note = "use verify instead")]
+    pub fn check(&self, token: &str, time: u64) -> bool {
+        self.verify(token, time)
+    }
+
+    /// Verify if token is valid given the provided timestamp
+    /// in seconds, accounting [skew](struct.TOTP.html#structfield.skew)
+    ///
+    /// Tokens that are not [digits](struct.TOTP.html#structfield.digits)
+    /// long or contain non-numeric characters are rejected before any
+    /// signature is computed.
+    pub fn verify(&self, token: &str, time: u64) -> bool {
+        self.verify_bytes(token.as_bytes(), time)
+    }
+
+    /// Verify if token given as bytes is valid given the provided
+    /// timestamp in seconds, accounting [skew](struct.TOTP.html#structfield.skew)
+    ///
+    /// The same as [verify](struct.TOTP.html#method.verify) without
+    /// requiring the token to be UTF-8, for example when it is read
+    /// from a binary protocol.
+    pub fn verify_bytes(&self, token: &[u8], time: u64) -> bool {
+        self.check_token(token, time, |r| self.encode_token(r))
+    }
+
+    /// Whether the token has the length and characters of a
+    /// generated token.
+    fn is_token(&self, token: &[u8]) -> bool {
+        if self.steam {
+            return token.len() == STEAM_DIGITS
+                && token.iter().all(|b| STEAM_CHARS.contains(b));
+        }
+        token.len() == self.digits.get()
+            && token.iter().all(|b| b.is_ascii_digit())
+    }
+
+    fn check_token<F>(&self, token: &[u8], time: u64, generate: F) -> bool
+    where
+        F: Fn(u32) -> String,
+    {
+        self.is_token(token) && self.check_with(token, time, generate)
+    }
+
+    /// Check if a Steam Guard token is valid given the provided
+    /// timestamp in seconds, accounting [skew](struct.TOTP.html#structfield.skew)
+    pub fn check_steam(&self, token: &str, time: u64) -> bool {
+        self.check_with(token.as_bytes(), time, steam_token)
+    }
+
+    /// Check if token is valid given the provided timestamp in seconds
+    /// and report which algorithm matched.
+    ///
+    /// The configured [algorithm](struct.TOTP.html#structfield.algorithm)
+    /// is tried first followed by each algorithm in `also_try`.
+    ///
+    /// Accepting additional algorithms widens the set of tokens that
+    /// are considered valid so it should only be used to detect clients
+    /// that silently fell back to another algorithm (typically SHA1);
+    /// a match on anything other than the configured algorithm should
+    /// be treated as a misconfiguration to be fixed by re-provisioning.
+    pub fn verify_with_algorithm_report(
+        &self,
+        token: &str,
+        time: u64,
+        also_try: &[Algorithm],
+    ) -> Option<Algorithm> {
+        if self.verify(token, time) {
+            return Some(self.algorithm);
+        }
+
+        let mut totp = self.clone();
+        for algorithm in also_try {
+            if *algorithm == self.algorithm {
+                continue;
+            }
+            totp.algorithm = *algorithm;
+            if totp.verify(token, time) {
+                return Some(*algorithm);
+            }
+        }
+        None
+    }
+
+    /// Check if token is valid given the provided timestamp in seconds
+    /// and has not already been used, accounting [skew](struct.TOTP.html#structfield.skew).
+    ///
+    /// The token is only accepted when the step it matches is strictly
+    /// greater than `last_used_step`; on success `last_used_step` is
+    /// updated to the matched step which the caller should persist
+    /// between requests to prevent a token from being replayed.
+    pub fn check_and_consume(
+        &self,
+        token: &str,
+        time: u64,
+        last_used_step: &mut Option<u64>,
+    ) -> bool {
+        let token = token.as_bytes();
+        if !self.is_token(token) {
+            return false;
+        }
+        match self.find_step(token, time, |r| self.encode_token(r)) {
+            Some(step) if last_used_step.is_none_or(|last| step > last) => {
+                *last_used_step = Some(step);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if token is valid given the provided timestamp in seconds,
+    /// accounting [skew](struct.TOTP.html#structfield.skew), and return
+    /// the number of seconds until the matched step expires.
+    ///
+    /// The remaining time is measured from the end of the matched step
+    /// so a token from a past step within the skew returns `Some(0)`
+    /// and a token from a future step returns more than one step.
+    pub fn check_with_expiry(&self, token: &str, time: u64) -> Option<u64> {
+        let token = token.as_bytes();
+        if !self.is_token(token) {
+            return None;
+        }
+        self.find_step(token, time, |r| self.encode_token(r))
+            .map(|step| {
+                self.step_time(step.saturating_add(1)).saturating_sub(time)
+            })
+    }
+
+    /// Check if token is valid given the provided timestamp in seconds,
+    /// accounting [skew](struct.TOTP.html#structfield.skew), and return
+    /// the absolute counter of the matched step.
+    ///
+    /// Unlike the offset returned by [check_window](struct.TOTP.html#method.check_window)
+    /// the counter does not depend on the timestamp of the check so it
+    /// can be stored, for example in an audit log of authentications.
+    pub fn check_returning_counter(
+        &self,
+        token: &str,
+        time: u64,
+    ) -> Option<u64> {
+        let token = token.as_bytes();
+        if !self.is_token(token) {
+            return None;
+        }
+        self.find_step(token, time, |r| self.encode_token(r))
+    }
+
+    /// Check if token is valid given the provided timestamp in seconds
+    /// accepting only the current step and the steps before it within
+    /// the [skew](struct.TOTP.html#structfield.skew).
+    ///
+    /// A client whose clock is behind sends a token from a past step,
+    /// which is tolerated, but a token from a future step is rejected:
+    /// it can only come from a clock that is ahead or from a token that
+    /// was computed in advance, for example by an attacker who obtained
+    /// the secret or the device and generated tokens to use later.
+    /// When [asymmetric_skew](struct.TOTP.html#method.asymmetric_skew)
+    /// is set only its `before` steps are accepted.
+    pub fn check_strict(&self, token: &str, time: u64) -> bool {
+        let token = token.as_bytes();
+        if !self.is_token(token) {
+            return false;
+        }
+        let before = self.window().before as u64;
+        self.find_step_in(token, time, before, 0, |r| self.encode_token(r))
+            .is_some()
+    }
+
+    fn check_with<F>(&self, token: &[u8], time: u64, generate: F) -> bool
+    where
+        F: Fn(u32) -> String,
+    {
+        self.find_step(token, time, generate).is_some()
+    }
+
+    /// Check if token is valid within a window of steps around the
+    /// provided timestamp in seconds, ignoring [skew](struct.TOTP.html#structfield.skew).
+    ///
+    /// Every step from `back_steps` before to `forward_steps` after
+    /// the step for the timestamp is compared so the time taken does
+    /// not depend on which step matched. Returns the offset of the
+    /// matched step relative to the step for the timestamp.
+    pub fn check_window(
+        &self,
+        token: &str,
+        time: u64,
+        back_steps: u64,
+        forward_steps: u64,
+    ) -> Option<i64> {
+        let token = token.as_bytes();
+        if !self.is_token(token) {
+            return None;
+        }
+        let counter = self.counter(time);
+        self.find_step_in(token, time, back_steps, forward_steps, |r| {
+            self.encode_token(r)
+        })
+        .map(|step| step as i64 - counter as i64)
+    }
+
+    /// Find the drift of a token, for example a hardware token whose
+    /// clock is not synchronized, given the provided timestamp in seconds.
+    ///
+    /// Every step up to `search_steps` before and after the step for
+    /// the timestamp is compared, the same as [check_window](struct.TOTP.html#method.check_window),
+    /// and the offset of the matched step is returned so it can be
+    /// stored and applied to the timestamp of later checks.
+    ///
+    /// The wider the window the more likely a token matches a step by
+    /// chance so this should only be used for a one-time resync,
+    /// ideally confirmed with the next token, never to verify a login.
+    pub fn resync(
+        &self,
+        token: &str,
+        time: u64,
+        search_steps: u64,
+    ) -> Option<i64> {
+        self.check_window(token, time, search_steps, search_steps)
+    }
+
+    /// Number of steps accepted before and after the current step.
+    fn window(&self) -> Skew {
+        self.asymmetric_skew.unwrap_or(Skew {
+            before: self.skew,
+            after: self.skew,
+        })
+    }
+
+    /// Find the most recent step within the skew that matches the token.
+    fn find_step<F>(
+        &self,
+        token: &[u8],
+        time: u64,
+        generate: F,
+    ) -> Option<u64>
+    where
+        F: Fn(u32) -> String,
+    {
+        let skew = self.window();
+        self.find_step_in(
+            token,
+            time,
+            skew.before as u64,
+            skew.after as u64,
+            generate,
+        )
+    }
+
+    /// Find the most recent step within the window that matches the token.
+    fn find_step_in<F>(
+        &self,
+        token: &[u8],
+        time: u64,
+        back_steps: u64,
+        forward_steps: u64,
+        generate: F,
+    ) -> Option<u64>
+    where
+        F: Fn(u32) -> String,
+    {
+        // Key the HMAC once and clone it for each step.
+        let mac = self.algorithm.keyed(self.secret.as_ref()).ok()?;
+        self.find_step_with(token, time, back_steps, forward_steps, |step| {
+            Some(generate(mac.sign_truncated(&self.counter_bytes(step))))
+        })
+    }
+
+    /// The counters from `back_steps` before to `forward_steps` after
+    /// the step for the provided timestamp in seconds, shared by
+    /// verification and [valid_codes](struct.TOTP.html#method.valid_codes)
+    /// so both cover the same steps.
+    fn window_range(
+        &self,
+        time: u64,
+        back_steps: u64,
+        forward_steps: u64,
+    ) -> Result<RangeInclusive<u64>> {
+        let counter = self.checked_counter(time)?;
+        Ok(counter.saturating_sub(back_steps)
+            ..=counter.saturating_add(forward_steps))
+    }
+
+    /// Find the most recent step within the window whose token, as
+    /// returned by `sign` for the counter, matches the token.
+    ///
+    /// Every step is compared so the time taken does not depend on
+    /// which step matched; a step that cannot be signed never matches.
+    fn find_step_with<S>(
+        &self,
+        token: &[u8],
+        time: u64,
+        back_steps: u64,
+        forward_steps: u64,
+        sign: S,
+    ) -> Option<u64>
+    where
+        S: Fn(u64) -> Option<String>,
+    {
+        let mut matched = None;
+        let steps = self.window_range(time, back_steps, forward_steps);
+        for step in steps.ok()? {
+            if let Some(code) = sign(step) {
+                if constant_time_eq(code.as_bytes(), token) {
+                    matched = Some(step);
+                }
+            }
+        }
+        matched
+    }
+
+    /// Check if token is valid by current system time, 
+    /// accounting [skew](struct.TOTP.html#structfield.skew).
+    #[cfg(feature = "std")]
+    #[deprecated(note = "use verify_current instead")]
+    pub fn check_current(&self, token: &str) -> Result<bool> {
+        self.verify_current(token)
+    }
+
+    /// Verify if token is valid by current system time,
+    /// accounting [skew](struct.TOTP.html#structfield.skew).
+    #[cfg(feature = "std")]
+    pub fn verify_current(&self, token: &str) -> Result<bool> {
+        let t = system_time()?;
+        Ok(self.verify(token, t))
+    }
+
+    /// Verify if token is valid for a `chrono` date time, accounting
+    /// [skew](struct.TOTP.html#structfield.skew), see
+    /// [generate_at](struct.TOTP.html#method.generate_at).
+    #[cfg(feature = "chrono")]
+    pub fn check_at(&self, token: &str, time: DateTime<Utc>) -> Result<bool> {
+        Ok(self.verify(token, unix_timestamp(time.timestamp())?))
+    }
+
+    /// Verify if token is valid for a `time` date time, accounting
+    /// [skew](struct.TOTP.html#structfield.skew), see
+    /// [generate_at_offset](struct.TOTP.html#method.generate_at_offset).
+    #[cfg(feature = "time")]
+    pub fn check_at_offset(
+        &self,
+        token: &str,
+        time: OffsetDateTime,
+    ) -> Result<bool> {
+        Ok(self.verify(token, unix_timestamp(time.unix_timestamp())?))
+    }
+
+    /// Every token accepted by [verify](struct.TOTP.html#method.verify)
+    /// given the provided timestamp in seconds.
+    ///
+    /// The tokens are for each step within the
+    /// [skew](struct.TOTP.html#structfield.skew) in step order, so the
+    /// token for the current step is in the middle when the skew is
+    /// symmetric. Useful to diagnose why a token was rejected.
+    pub fn valid_codes(&self, time: u64) -> Result<Vec<String>> {
+        let mac = self.algorithm.keyed(self.secret.as_ref())?;
+        let skew = self.window();
+        Ok(self
+            .window_range(time, skew.before as u64, skew.after as u64)?
+            .map(|step| {
+                self.encode_token(
+                    mac.sign_truncated(&self.counter_bytes(step)),
+                )
+            })
+            .collect())
+    }
+
+    /// Every token accepted by [verify_current](struct.TOTP.html#method.verify_current)
+    /// at the current system time.
+    #[cfg(feature = "std")]
+    pub fn valid_codes_current(&self) -> Result<Vec<String>> {
+        let t = system_time()?;
+        self.valid_codes(t)
+    }
+
+    /// The token for each algorithm given the provided timestamp in
+    /// seconds, keeping the secret, digits and step.
+    ///
+    /// Useful to diagnose an authenticator app that silently fell back
+    /// to another algorithm (typically SHA1); SHA256 and SHA512 are
+    /// only included with the `sha2` feature.
+    pub fn debug_codes(&self, time: u64) -> Result<Vec<(Algorithm, String)>> {
+        ALGORITHMS
+            .iter()
+            .map(|algorithm| {
+                Ok((
+                    *algorithm,
+                    self.with_algorithm(*algorithm).generate(time)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// The token for each algorithm at the current system time, see
+    /// [debug_codes](struct.TOTP.html#method.debug_codes).
+    #[cfg(feature = "std")]
+    pub fn debug_codes_current(&self) -> Result<Vec<(Algorithm, String)>> {
+        let t = system_time()?;
+        self.debug_codes(t)
+    }
+
+    /// Return the base32 representation of the secret, which 
+    /// might be useful when users want to manually add the 
+    /// secret to their authenticator.
+    pub fn to_secret_base32(&self) -> String {
+        self.to_secret_base32_with(base32::Alphabet::Rfc4648 {
+            padding: false,
+        })
+    }
+
+    /// Return the base32 representation of the secret using
+    /// the given alphabet, for example `Crockford`.
+    pub fn to_secret_base32_with(
+        &self,
+        alphabet: base32::Alphabet,
+    ) -> String {
+        base32::encode(alphabet, self.secret.as_ref())
+    }
+
+    /// Return the base32 representation of the secret in groups of
+    /// four characters separated by a space (`KRSX G5CT MVRX`) which
+    /// is easier to read when the secret is entered manually.
+    ///
+    /// The spaces are ignored by [from_secret_base32](struct.TOTP.html#method.from_secret_base32).
+    pub fn to_secret_base32_grouped(&self) -> String {
+        let secret = self.to_secret_base32();
+        let mut grouped = String::with_capacity(secret.len() * 5 / 4);
+        for (i, c) in secret.chars().enumerate() {
+            if i > 0 && i % 4 == 0 {
+                grouped.push(' ');
+            }
+            grouped.push(c);
+        }
+        grouped
+    }
+
+    /// Convert a base32 secret into a TOTP.
+    ///
+    /// The account name is the empty string and the issuer is None;
+    /// so you should set them explicitly after decoding the secret bytes.
+    ///
+    /// The skew is 1; use [from_secret_base32_with_skew](struct.TOTP.html#method.from_secret_base32_with_skew)
+    /// to choose the validation window.
+    pub fn from_secret_base32<S: AsRef<str>>(secret: S) -> Result<TOTP> {
+        TOTP::from_secret_base32_with_skew(secret, 1)
+    }
+
+    /// Convert a base32 secret into a TOTP with the given skew.
+    ///
+    /// The account name is the empty string and the issuer is None;
+    /// so you should set them explicitly after decoding the secret bytes.
+    pub fn from_secret_base32_with_skew<S: AsRef<str>>(
+        secret: S,
+        skew: u8,
+    ) -> Result<TOTP> {
+        TOTP::from_secret_base32_full(
+            secret,
+            Algorithm::SHA1,
+            6,
+            skew,
+            30,
+            String::new(),
+            None,
+        )
+    }
+
+    /// Convert a base32 secret into a TOTP with the given parameters.
+    ///
+    /// The secret is decoded the same as [from_secret_base32](struct.TOTP.html#method.from_secret_base32)
+    /// and the TOTP is validated by [new](struct.TOTP.html#method.new).
+    pub fn from_secret_base32_full<S: AsRef<str>>(
+        secret: S,
+        algorithm: Algorithm,
+        digits: usize,
+        skew: u8,
+        step: u64,
+        account_name: String,
+        issuer: Option<String>,
+    ) -> Result<TOTP> {
+        let buffer = decode_secret(secret.as_ref())?;
+
+        TOTP::new(algorithm, digits, skew, step, buffer, account_name, issuer)
+    }
+
+    /// Convert a base32 secret encoded with the given alphabet
+    /// into a TOTP.
+    ///
+    /// ASCII whitespace is removed before decoding.
+    ///
+    /// The account name is the empty string and the issuer is None;
+    /// so you should set them explicitly after decoding the secret bytes.
+    pub fn from_secret_base32_with<S: AsRef<str>>(
+        secret: S,
+        alphabet: base32::Alphabet,
+    ) -> Result<TOTP> {
+        let normalized: String = secret
+            .as_ref()
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect();
+        check_base32_len(&normalized)?;
+        let buffer = base32::decode(alphabet, &normalized)
+            .ok_or_else(|| Error::Secret(secret.as_ref().to_string()))?;
+
+        TOTP::new(Algorithm::SHA1, 6, 1, 30, buffer, String::new(), None)
+    }
+
+    /// Return the lowercase hex representation of the secret.
+    pub fn to_secret_hex(&self) -> String {
+        self.secret.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Convert a hex secret into a TOTP.
+    ///
+    /// Upper and lowercase hex digits are accepted.
+    ///
+    /// The account name is the empty string and the issuer is None;
+    /// so you should set them explicitly after decoding the secret bytes.
+    pub fn from_secret_hex<S: AsRef<str>>(secret: S) -> Result<TOTP> {
+        let buffer = decode_hex(secret.as_ref())?;
+        TOTP::new(Algorithm::SHA1, 6, 1, 30, buffer, String::new(), None)
+    }
+
+    /// Create a TOTP from the raw secret bytes to generate and verify
+    /// tokens, using the same settings as [new_default](struct.TOTP.html#method.new_default).
+    ///
+    /// Only the length of the secret is validated; the account name is
+    /// the empty string and the issuer is None so set the account name
+    /// before calling [get_url](struct.TOTP.html#method.get_url).
+    pub fn from_secret_bytes(secret: Vec<u8>) -> Result<TOTP> {
+        TOTP::new_default(secret, String::new(), None)
+    }
+
+    /// Generate a TOTP from the standard otpauth URL.
+    ///
+    /// The URL cannot express a skew so the skew is 0; tolerance for
+    /// clock drift is a server-side policy as per
+    /// [rfc-6238](https://tools.ietf.org/html/rfc6238#section-5.2).
+    /// Use [from_url_with_skew](struct.TOTP.html#method.from_url_with_skew)
+    /// to choose the validation window.
+    ///
+    /// Prior versions used a skew of 1.
+    ///
+    /// When a value in the URL does not pass the validation of
+    /// [new](struct.TOTP.html#method.new), for example a secret that is
+    /// too short, the error is wrapped in [Error::UrlImport](enum.Error.html#variant.UrlImport).
+    pub fn from_url<S: AsRef<str>>(url: S) -> Result<TOTP> {
+        TOTP::try_from(Url::parse(url.as_ref())?)
+    }
+
+    /// Generate a TOTP from an otpauth URL created by a provisioner
+    /// that does not follow the format exactly.
+    ///
+    /// The `totp` (or `steam`) host is matched ignoring case, so
+    /// `otpauth://TOTP/...` is accepted, and a missing host is tolerated
+    /// when the label is in the path, for example `otpauth:///Github:mock`;
+    /// a first path segment of `totp` is then treated as the host.
+    /// Otherwise this is the same as [from_url](struct.TOTP.html#method.from_url).
+    ///
+    /// Accepting malformed URLs means a typo in the host goes unnoticed
+    /// and a label which happens to start with `totp/` loses that segment
+    /// so prefer [from_url](struct.TOTP.html#method.from_url) unless the
+    /// URLs come from a provisioner that is known to be broken. HOTP
+    /// URLs are still rejected as their tokens use a counter.
+    pub fn from_url_lenient<S: AsRef<str>>(url: S) -> Result<TOTP> {
+        let url = Url::parse(url.as_ref())?;
+        if url.scheme() != "otpauth" {
+            return Err(Error::Scheme(url.scheme().to_string()));
+        }
+
+        let is_host = |host: &str| {
+            host.eq_ignore_ascii_case("totp")
+                || host.eq_ignore_ascii_case("steam")
+        };
+        let path = url.path().trim_start_matches('/');
+        let (host, label) = match url.host_str() {
+            Some(host) if is_host(host) => (host, path),
+            Some(host) => return Err(Error::Host(host.to_string())),
+            None => match path.split_once('/') {
+                Some((host, label)) if is_host(host) => (host, label),
+                _ => ("totp", path),
+            },
+        };
+
+        let url = Url::parse(&format!(
+            "otpauth://{}/{}?{}",
+            host.to_ascii_lowercase(),
+            label,
+            url.query().unwrap_or_default()
+        ))?;
+        Ok(TOTP::from_parsed_url(url, 0, true)?.0)
+    }
+
+    /// Generate a TOTP from the standard otpauth URL with the given skew.
+    pub fn from_url_with_skew<S: AsRef<str>>(
+        url: S,
+        skew: u8,
+    ) -> Result<TOTP> {
+        Ok(TOTP::from_parsed_url(Url::parse(url.as_ref())?, skew, true)?.0)
+    }
+
+    /// Generate a TOTP from the standard otpauth URL allowing `:` in
+    /// the account name and issuer when it is percent-encoded as `%3A`.
+    ///
+    /// This reads the URLs created by [get_url](struct.TOTP.html#method.get_url)
+    /// for a TOTP from [new_unchecked_label](struct.TOTP.html#method.new_unchecked_label);
+    /// otherwise this is the same as [from_url](struct.TOTP.html#method.from_url).
+    pub fn from_url_unchecked_label<S: AsRef<str>>(url: S) -> Result<TOTP> {
+        Ok(TOTP::from_parsed_url(Url::parse(url.as_ref())?, 0, false)?.0)
+    }
+
+    /// Generate a TOTP from the standard otpauth URL and return the
+    /// query parameters that are not supported.
+    ///
+    /// Parameters are returned decoded in the order they appear in the
+    /// URL; otherwise this is the same as [from_url](struct.TOTP.html#method.from_url).
+    pub fn from_url_verbose<S: AsRef<str>>(
+        url: S,
+    ) -> Result<(TOTP, Vec<(String, String)>)> {
+        TOTP::from_parsed_url(Url::parse(url.as_ref())?, 0, true)
+    }
+
+    /// Read the values of the URL, checking everything that does not
+    /// need a TOTP to be constructed.
+    ///
+    /// The secret is not checked so this is shared by
+    /// `from_parsed_url`, `validate_url` and `url_params`.
+    fn parse_url(url: &Url) -> Result<UrlParts> {
+        check_otpauth_url(url)?;
+
+        let mut algorithm = Algorithm::SHA1;
+        let mut digits = 6;
+        let mut step = 30;
+        let mut t0 = 0;
+        let mut image = None;
+        let mut secret = String::new();
+        let mut account_name: String;
+        let mut issuer: Option<String> = None;
+        let mut encoder_steam = false;
+        let mut unsupported = Vec::new();
+
+        let path = url.path().trim_start_matches('/');
+        if path.contains(':') {
+            let parts = path.split_once(':').unwrap();
+            issuer =
+                Some(normalize(url_decode(parts.0).ok_or_else(|| {
+                    Error::IssuerDecoding(parts.0.to_owned())
+                })?));
+            account_name = parts.1.trim_start_matches(':').to_owned();
+        } else {
+            account_name = path.to_owned();
+        }
+
+        account_name = url_decode(account_name.as_str())
+            .ok_or_else(|| Error::AccountName(account_name.to_string()))?;
+
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "algorithm" => {
+                    algorithm = value.parse()?;
+                }
+                "digits" => {
+                    digits = value
+                        .parse::<usize>()
+                        .map_err(|_| Error::Digits(value.to_string()))?;
+                }
+                "period" => {
+                    step = value
+                        .parse::<u64>()
+                        .map_err(|_| Error::Step(value.to_string()))?;
+                }
+                "t0" | "epoch" => {
+                    t0 = value
+                        .parse::<u64>()
+                        .map_err(|_| Error::T0(value.to_string()))?;
+                }
+                "image" => {
+                    image = Some(value.to_string());
+                }
+                "secret" => {
+                    secret = value.to_string();
+                }
+                "issuer" => {
+                    let param_issuer = normalize(
+                        value
+                            .parse::<String>()
+                            .map_err(|_| Error::Issuer(value.to_string()))?,
+                    );
+                    if let Some(issuer) = &issuer {
+                        if param_issuer.as_str() != issuer {
+                            return Err(Error::IssuerMismatch(
+                                issuer.to_string(),
+                                param_issuer,
+                            ));
+                        }
+                    }
+                    issuer = Some(param_issuer);
+                }
+                "encoder" if value.eq_ignore_ascii_case("steam") => {
+                    encoder_steam = true;
+                }
+                _ => {
+                    unsupported.push((key.to_string(), value.to_string()));
+                }
+            }
+        }
+
+        // Steam is marked by the host or the encoder, not the issuer
+        // which an ordinary TOTP may share; the digits are ignored so
+        // the `digits=5` some apps emit is accepted.
+        let steam = url.host_str() == Some("steam") || encoder_steam;
+        if steam && digits == STEAM_DIGITS {
+            digits = 6;
+        }
+
+        Ok(UrlParts {
+            algorithm,
+            digits,
+            step,
+            t0,
+            image,
+            secret,
+            account_name: normalize(account_name),
+            issuer,
+            steam,
+            unsupported,
+        })
+    }
+
+    /// Parse the URL, allowing `:` in the account name and issuer
+    /// unless `check_label` is set.
+    fn from_parsed_url(
+        url: Url,
+        skew: u8,
+        check_label: bool,
+    ) -> Result<(TOTP, Vec<(String, String)>)> {
+        let parts = TOTP::parse_url(&url)?;
+        check_url_secret(&parts.secret)?;
+        let secret = decode_secret(&parts.secret)?;
+
+        // Give the context of the import when a value is not valid.
+        let new = if check_label {
+            TOTP::new
+        } else {
+            TOTP::new_unchecked_label
+        };
+        let mut totp = new(
+            parts.algorithm,
+            parts.digits,
+            skew,
+            parts.step,
+            secret,
+            parts.account_name,
+            parts.issuer,
+        )
+        .map_err(|e| Error::UrlImport(Box::new(e)))?;
+        totp.t0 = parts.t0;
+        totp.image = parts.image;
+        totp.steam = parts.steam;
+        Ok((totp, parts.unsupported))
+    }
+
+    /// Encode as a single line that is safe to store in an
+    /// environment variable.
+    ///
+    /// The format is
+    /// `TOTP_v2:ALGORITHM:DIGITS:SKEW:STEP:SECRET:ACCOUNT:ISSUER:T0:IMAGE:OPTIONS`
+    /// where the secret is base32 and the account name, issuer and
+    /// image are percent-encoded; a missing issuer or image is the
+    /// empty string. The options are a comma-separated list of
+    /// `steam`, `little-endian`, `skew=BEFORE-AFTER` for the
+    /// asymmetric skew, `unchecked-skew` when a skew is above
+    /// [MAX_SKEW](constant.MAX_SKEW.html) and `unchecked-label` when
+    /// the account name or issuer contains a colon.
+    pub fn to_env_string(&self) -> String {
+        let mut options = Vec::new();
+        if self.steam {
+            options.push("steam".to_string());
+        }
+        if self.endianness == Endianness::Little {
+            options.push("little-endian".to_string());
+        }
+        if let Some(skew) = self.asymmetric_skew {
+            options.push(format!("skew={}-{}", skew.before, skew.after));
+        }
+        if self.is_skew_unchecked() {
+            options.push("unchecked-skew".to_string());
+        }
+        if self.is_label_unchecked() {
+            options.push("unchecked-label".to_string());
+        }
+        format!(
+            "{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}",
+            ENV_PREFIX,
+            self.algorithm,
             self.digits,
+            self.skew,
+            self.step,
+            self.to_secret_base32(),
+            url_encode(&self.account_name),
+            url_encode(self.issuer.as_deref().unwrap_or_default()),
+            self.t0,
+            url_encode(self.image.as_deref().unwrap_or_default()),
+            options.join(","),
+        )
+    }
+
+    /// Decode from the representation created by
+    /// [to_env_string](struct.TOTP.html#method.to_env_string).
+    ///
+    /// The `TOTP_v1` format, which ends after the issuer, is still
+    /// decoded.
+    pub fn from_env_string(value: &str) -> Result<TOTP> {
+        let parts: Vec<&str> = value.trim().split(':').collect();
+        let v2 = match (parts[0], parts.len()) {
+            (ENV_PREFIX_V1, 8) => false,
+            (ENV_PREFIX, 11) => true,
+            _ => return Err(Error::EnvString(value.to_string())),
+        };
+
+        let algorithm = parts[1].parse()?;
+        let digits = parts[2]
+            .parse::<usize>()
+            .map_err(|_| Error::Digits(parts[2].to_string()))?;
+        let skew = parts[3]
+            .parse::<u8>()
+            .map_err(|_| Error::EnvString(value.to_string()))?;
+        let step = parts[4]
+            .parse::<u64>()
+            .map_err(|_| Error::Step(parts[4].to_string()))?;
+        let secret = decode_secret(parts[5])?;
+        let account_name = url_decode(parts[6])
+            .ok_or_else(|| Error::AccountName(parts[6].to_string()))?;
+        let issuer =
+            if parts[7].is_empty() {
+                None
+            } else {
+                Some(url_decode(parts[7]).ok_or_else(|| {
+                    Error::IssuerDecoding(parts[7].to_string())
+                })?)
+            };
+
+        if !v2 {
+            return TOTP::new(
+                algorithm,
+                digits,
+                skew,
+                step,
+                secret,
+                account_name,
+                issuer,
+            );
+        }
+
+        let t0 = parts[8]
+            .parse::<u64>()
+            .map_err(|_| Error::T0(parts[8].to_string()))?;
+        let image = if parts[9].is_empty() {
+            None
+        } else {
+            Some(
+                url_decode(parts[9])
+                    .ok_or_else(|| Error::EnvString(value.to_string()))?,
+            )
+        };
+        let mut steam = false;
+        let mut endianness = Endianness::Big;
+        let mut asymmetric_skew = None;
+        let mut unchecked_skew = false;
+        let mut unchecked_label = false;
+        for option in parts[10].split(',').filter(|o| !o.is_empty()) {
+            match option {
+                "steam" => steam = true,
+                "little-endian" => endianness = Endianness::Little,
+                "unchecked-skew" => unchecked_skew = true,
+                "unchecked-label" => unchecked_label = true,
+                _ => {
+                    let skew = option
+                        .strip_prefix("skew=")
+                        .and_then(|skew| skew.split_once('-'))
+                        .and_then(|(before, after)| {
+                            Some(Skew {
+                                before: before.parse().ok()?,
+                                after: after.parse().ok()?,
+                            })
+                        })
+                        .ok_or_else(|| Error::EnvString(value.to_string()))?;
+                    asymmetric_skew = Some(skew);
+                }
+            }
+        }
+
+        let new = if unchecked_label {
+            TOTP::new_unchecked_label
+        } else {
+            TOTP::new
+        };
+        // The skew is validated by with_decoded_skew.
+        let mut totp =
+            new(algorithm, digits, 0, step, secret, account_name, issuer)?
+                .with_decoded_skew(skew, asymmetric_skew, unchecked_skew)?;
+        totp.t0 = t0;
+        totp.image = image;
+        totp.steam = steam;
+        totp.endianness = endianness;
+        Ok(totp)
+    }
+
+    /// Encode in a compact binary format for storage.
+    ///
+    /// The format is a version byte (currently 2) followed by the
+    /// algorithm (0 for SHA1, 1 for SHA256 and 2 for SHA512), digits
+    /// and skew as single bytes, the step as a big-endian `u64`, the
+    /// secret and account name prefixed by their length as a
+    /// big-endian `u32` and a byte which is 1 when a length-prefixed
+    /// issuer follows or 0 when there is no issuer.
+    ///
+    /// Version 2 appends the [t0](struct.TOTP.html#structfield.t0) as a
+    /// big-endian `u64`, an options byte (bit 0 for Steam mode, bit 1
+    /// for a little-endian counter, bit 2 when a skew is above
+    /// [MAX_SKEW](constant.MAX_SKEW.html) and bit 3 when the account
+    /// name or issuer contains a colon), a byte which is 1 when the
+    /// asymmetric skew follows as two bytes (before and after) and a
+    /// byte which is 1 when a length-prefixed image URL follows.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let algorithm = match self.algorithm {
+            Algorithm::SHA1 => 0,
+            #[cfg(feature = "sha2")]
+            Algorithm::SHA256 => 1,
+            #[cfg(feature = "sha2")]
+            Algorithm::SHA512 => 2,
+        };
+        let mut bytes = vec![
+            BYTES_VERSION,
+            algorithm,
+            self.digits.get() as u8,
+            self.skew,
+        ];
+        bytes.extend_from_slice(&self.step.to_be_bytes());
+        put_prefixed(&mut bytes, &self.secret);
+        put_prefixed(&mut bytes, self.account_name.as_bytes());
+        match &self.issuer {
+            Some(issuer) => {
+                bytes.push(1);
+                put_prefixed(&mut bytes, issuer.as_bytes());
+            }
+            None => bytes.push(0),
+        }
+        bytes.extend_from_slice(&self.t0.to_be_bytes());
+        let mut options = 0;
+        if self.steam {
+            options |= BYTES_STEAM;
+        }
+        if self.endianness == Endianness::Little {
+            options |= BYTES_LITTLE_ENDIAN;
+        }
+        if self.is_skew_unchecked() {
+            options |= BYTES_UNCHECKED_SKEW;
+        }
+        if self.is_label_unchecked() {
+            options |= BYTES_UNCHECKED_LABEL;
+        }
+        bytes.push(options);
+        match self.asymmetric_skew {
+            Some(skew) => {
+                bytes.extend_from_slice(&[1, skew.before, skew.after])
+            }
+            None => bytes.push(0),
+        }
+        match &self.image {
+            Some(image) => {
+                bytes.push(1);
+                put_prefixed(&mut bytes, image.as_bytes());
+            }
+            None => bytes.push(0),
+        }
+        bytes
+    }
+
+    /// Decode from the representation created by
+    /// [to_bytes](struct.TOTP.html#method.to_bytes).
+    ///
+    /// Version 1, which has no trailing fields, is still decoded.
+    /// Malformed input is reported as
+    /// [Error::Decode](enum.Error.html#variant.Decode); the decoded
+    /// values are validated the same as [new](struct.TOTP.html#method.new).
+    pub fn from_bytes(bytes: &[u8]) -> Result<TOTP> {
+        let mut bytes = bytes;
+        let header = take_bytes(&mut bytes, 4)?;
+        if header[0] != 1 && header[0] != BYTES_VERSION {
+            return Err(Error::Decode(format!(
+                "unsupported version {}",
+                header[0]
+            )));
+        }
+        let algorithm = match header[1] {
+            0 => Algorithm::SHA1,
+            #[cfg(feature = "sha2")]
+            1 => Algorithm::SHA256,
+            #[cfg(feature = "sha2")]
+            2 => Algorithm::SHA512,
+            other => {
+                return Err(Error::Decode(format!(
+                    "unknown algorithm {}",
+                    other
+                )))
+            }
+        };
+        let digits = header[2] as usize;
+        let skew = header[3];
+        let step = u64::from_be_bytes(
+            take_bytes(&mut bytes, 8)?.try_into().unwrap(),
+        );
+        let secret = take_prefixed(&mut bytes)?.to_vec();
+        let account_name = take_string(&mut bytes)?;
+        let issuer = match take_bytes(&mut bytes, 1)?[0] {
+            0 => None,
+            1 => Some(take_string(&mut bytes)?),
+            other => {
+                return Err(Error::Decode(format!(
+                    "invalid issuer flag {}",
+                    other
+                )))
+            }
+        };
+
+        let mut t0 = 0;
+        let mut options = 0;
+        let mut asymmetric_skew = None;
+        let mut image = None;
+        if header[0] == BYTES_VERSION {
+            t0 = u64::from_be_bytes(
+                take_bytes(&mut bytes, 8)?.try_into().unwrap(),
+            );
+            options = take_bytes(&mut bytes, 1)?[0];
+            if options
+                & !(BYTES_STEAM
+                    | BYTES_LITTLE_ENDIAN
+                    | BYTES_UNCHECKED_SKEW
+                    | BYTES_UNCHECKED_LABEL)
+                != 0
+            {
+                return Err(Error::Decode(format!(
+                    "invalid options {}",
+                    options
+                )));
+            }
+            asymmetric_skew = match take_bytes(&mut bytes, 1)?[0] {
+                0 => None,
+                1 => {
+                    let skew = take_bytes(&mut bytes, 2)?;
+                    Some(Skew {
+                        before: skew[0],
+                        after: skew[1],
+                    })
+                }
+                other => {
+                    return Err(Error::Decode(format!(
+                        "invalid asymmetric skew flag {}",
+                        other
+                    )))
+                }
+            };
+            image = match take_bytes(&mut bytes, 1)?[0] {
+                0 => None,
+                1 => Some(take_string(&mut bytes)?),
+                other => {
+                    return Err(Error::Decode(format!(
+                        "invalid image flag {}",
+                        other
+                    )))
+                }
+            };
+        }
+        if !bytes.is_empty() {
+            return Err(Error::Decode(
+                "unexpected trailing data".to_string(),
+            ));
+        }
+
+        let new = if options & BYTES_UNCHECKED_LABEL != 0 {
+            TOTP::new_unchecked_label
+        } else {
+            TOTP::new
+        };
+        // The skew is validated by with_decoded_skew.
+        let mut totp =
+            new(algorithm, digits, 0, step, secret, account_name, issuer)?
+                .with_decoded_skew(
+                    skew,
+                    asymmetric_skew,
+                    options & BYTES_UNCHECKED_SKEW != 0,
+                )?;
+        totp.t0 = t0;
+        totp.steam = options & BYTES_STEAM != 0;
+        if options & BYTES_LITTLE_ENDIAN != 0 {
+            totp.endianness = Endianness::Little;
+        }
+        totp.image = image;
+        Ok(totp)
+    }
+
+    /// Read the algorithm, digits and period from an otpauth URL.
+    ///
+    /// The secret is not decoded so it may be missing or a placeholder;
+    /// useful when the secret is supplied separately, for example
+    /// from a vault. Otherwise the URL is parsed and checked by the
+    /// same code as [from_url](struct.TOTP.html#method.from_url).
+    pub fn url_params(url: &str) -> Result<(Algorithm, usize, u64)> {
+        let parts = TOTP::parse_url(&Url::parse(url)?)?;
+        check_params(parts.digits, 0, parts.step, None)
+            .map(|_| (parts.algorithm, parts.digits, parts.step))
+            .map_err(|e| Error::UrlImport(Box::new(e)))
+    }
+
+    /// Check that an otpauth URL can be imported with
+    /// [from_url](struct.TOTP.html#method.from_url) without
+    /// constructing the TOTP.
+    ///
+    /// The URL is parsed and checked by the same code as `from_url`,
+    /// except that the secret is not decoded, so the result and the
+    /// error are the same as `from_url` for every URL.
+    pub fn validate_url(url: &str) -> Result<()> {
+        let parts = TOTP::parse_url(&Url::parse(url)?)?;
+        let secret_len = check_url_secret(&parts.secret)?;
+        check_params(parts.digits, 0, parts.step, Some(secret_len))
+            .and_then(|_| {
+                check_label(&parts.account_name, parts.issuer.as_deref())
+            })
+            .map_err(|e| Error::UrlImport(Box::new(e)))
+    }
+
+    /// Generate a standard URL used to automatically add TOTP auths.
+    ///
+    /// Usually used with a QR code.
+    ///
+    /// Label and issuer will be URL-encoded; the secret will be 
+    /// converted to base32 without padding, as per the RFC.
+    /// A space is always encoded as `%20` and a `+` as `%2B` so
+    /// both survive [from_url](struct.TOTP.html#method.from_url).
+    ///
+    /// Parameters are always emitted in the order used by Google
+    /// Authenticator; `secret`, `issuer` (when set), `algorithm`,
+    /// `digits` and `period`, which some strict importers require.
+    ///
+    /// The [t0](struct.TOTP.html#structfield.t0) parameter is only
+    /// included when it is not zero and the [image](struct.TOTP.html#structfield.image)
+    /// parameter only when it is set, both after `period`. In
+    /// [Steam mode](struct.TOTP.html#method.is_steam) the URL ends
+    /// with `encoder=steam` so it is read back in Steam mode.
+    ///
+    /// An empty [account_name](struct.TOTP.html#structfield.account_name),
+    /// for example after [from_secret_base32](struct.TOTP.html#method.from_secret_base32),
+    /// gives an empty label (`otpauth://totp/?secret=...`) or only the
+    /// issuer prefix (`otpauth://totp/Github:?secret=...`) which
+    /// [from_url](struct.TOTP.html#method.from_url) reads back as an empty
+    /// account name; most authenticator apps expect an account name so
+    /// it should be set before the URL is shown to a user.
+    pub fn get_url(&self) -> String {
+        let account_name: String = url_encode(self.account_name.as_str());
+        // The issuer prefix of the label and the issuer parameter
+        // share the same encoding so they always match.
+        let (label, issuer) = match &self.issuer {
+            Some(issuer) => {
+                let issuer: String = url_encode(issuer.as_str());
+                (
+                    format!("{}:{}", issuer, account_name),
+                    format!("&issuer={}", issuer),
+                )
+            }
+            None => (account_name, String::new()),
+        };
+
+        let mut url = format!(
+            "otpauth://totp/{}?secret={}{}&algorithm={}&digits={}&period={}",
+            label,
+            self.to_secret_base32(),
+            issuer,
             self.algorithm,
+            self.digits,
+            self.step,
+        );
+        if self.t0 != 0 {
+            url.push_str(&format!("&t0={}", self.t0));
+        }
+        if let Some(image) = &self.image {
+            url.push_str(&format!("&image={}", url_encode(image)));
+        }
+        if self.steam {
+            url.push_str("&encoder=steam");
+        }
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    /// Seed for HMAC-SHA1 from [rfc-6238](https://tools.ietf.org/html/rfc6238#appendix-B).
+    const RFC6238_SEED_SHA1: &str =
+        "3132333435363738393031323334353637383930";
+
+    /// Seed for HMAC-SHA256 from [rfc-6238](https://tools.ietf.org/html/rfc6238#appendix-B).
+    #[cfg(feature = "sha2")]
+    const RFC6238_SEED_SHA256: &str =
+        "3132333435363738393031323334353637383930313233343536373839303132";
+
+    /// Seed for HMAC-SHA512 from [rfc-6238](https://tools.ietf.org/html/rfc6238#appendix-B).
+    #[cfg(feature = "sha2")]
+    const RFC6238_SEED_SHA512: &str = "31323334353637383930313233343536373839303132333435363738393031323334353637383930313233343536373839303132333435363738393031323334";
+
+    /// Test vectors from [rfc-6238](https://tools.ietf.org/html/rfc6238#appendix-B)
+    /// as the time followed by the SHA1, SHA256 and SHA512 tokens.
+    const RFC6238_VECTORS: [(u64, &str, &str, &str); 6] = [
+        (59, "94287082", "46119246", "90693936"),
+        (1111111109, "07081804", "68084774", "25091201"),
+        (1111111111, "14050471", "67062674", "99943326"),
+        (1234567890, "89005924", "91819424", "93441116"),
+        (2000000000, "69279037", "90698825", "38618901"),
+        (20000000000, "65353130", "77737706", "47863826"),
+    ];
+
+    fn rfc6238(seed: &str, algorithm: Algorithm) -> TOTP {
+        let mut totp = TOTP::from_secret_hex(seed).unwrap();
+        totp.algorithm = algorithm;
+        totp.digits = Digits::Eight;
+        totp
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn algorithm_from_str() {
+        assert_eq!("SHA1".parse::<Algorithm>().unwrap(), Algorithm::SHA1);
+        assert_eq!("sha256".parse::<Algorithm>().unwrap(), Algorithm::SHA256);
+        assert_eq!(Algorithm::try_from("Sha512").unwrap(), Algorithm::SHA512);
+        assert!(matches!(
+            "MD5".parse::<Algorithm>(),
+            Err(Error::Algorithm(_))
+        ));
+        for algorithm in
+            [Algorithm::SHA1, Algorithm::SHA256, Algorithm::SHA512]
+        {
+            assert_eq!(
+                algorithm.to_string().parse::<Algorithm>().unwrap(),
+                algorithm
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn algorithm_output_len() {
+        for algorithm in
+            [Algorithm::SHA1, Algorithm::SHA256, Algorithm::SHA512]
+        {
+            assert_eq!(
+                algorithm.sign(&[0; 20], &[0; 8]).unwrap().len(),
+                algorithm.output_len()
+            );
+        }
+        assert_eq!(Algorithm::SHA1.output_len(), 20);
+        assert_eq!(Algorithm::SHA256.output_len(), 32);
+        assert_eq!(Algorithm::SHA512.output_len(), 64);
+    }
+
+    #[test]
+    fn new_wrong_issuer() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github:".to_string()),
+        );
+        assert!(totp.is_err());
+        assert!(matches!(totp.unwrap_err(), Error::Issuer(_)));
+    }
+
+    #[test]
+    fn new_wrong_account_name() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock:example.com".to_string(),
+            Some("Github".to_string()),
+        );
+        assert!(totp.is_err());
+        assert!(matches!(totp.unwrap_err(), Error::AccountName(_)));
+    }
+
+    #[test]
+    fn new_wrong_account_name_no_issuer() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock:example.com".to_string(),
+            None,
+        );
+        assert!(totp.is_err());
+        assert!(matches!(totp.unwrap_err(), Error::AccountName(_)));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn new_strict_secret_length() {
+        let secret = "TestSecretSuperSecretTestSecret!".as_bytes().to_vec();
+        assert_eq!(secret.len(), 32);
+        assert!(TOTP::new_strict(
+            Algorithm::SHA256,
+            6,
+            1,
+            30,
+            secret.clone(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .is_ok());
+        let totp = TOTP::new_strict(
+            Algorithm::SHA512,
+            6,
+            1,
+            30,
+            secret.clone(),
+            "mock@example.com".to_string(),
+            None,
+        );
+        assert!(matches!(totp.unwrap_err(), Error::SecretTooSmall(256)));
+        assert!(TOTP::new(
+            Algorithm::SHA512,
+            6,
+            1,
+            30,
+            secret,
+            "mock@example.com".to_string(),
+            None,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn comparison_ok() {
+        let reference = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        let test = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        assert_eq!(reference, test);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn comparison_all_fields() {
+        let reference = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        let test = TOTP::new(
+            Algorithm::SHA256,
+            8,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_ne!(reference, test);
+        assert!(reference.same_secret(&test));
+        let mut other = reference.clone();
+        other.secret = "OtherSecretSuperSecret".as_bytes().to_vec();
+        assert_ne!(reference, other);
+        assert!(!reference.same_secret(&other));
+    }
+
+    #[test]
+    fn url_for_secret_matches_sha1_without_issuer() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let url = totp.get_url();
+        assert_eq!(url.as_str(), "otpauth://totp/mock%40example.com?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&algorithm=SHA1&digits=6&period=1");
+    }
+
+    #[test]
+    fn url_for_secret_matches_sha1() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        let url = totp.get_url();
+        assert_eq!(url.as_str(), "otpauth://totp/Github:mock%40example.com?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Github&algorithm=SHA1&digits=6&period=1");
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn url_canonical_order() {
+        let totp = TOTP::new(
+            Algorithm::SHA256,
+            8,
+            1,
+            60,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        assert_eq!(totp.get_url(), "otpauth://totp/Github:mock%40example.com?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Github&algorithm=SHA256&digits=8&period=60");
+        assert_eq!(TOTP::from_url(totp.get_url()).unwrap().step, 60);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn url_for_secret_matches_sha256() {
+        let totp = TOTP::new(
+            Algorithm::SHA256,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        let url = totp.get_url();
+        assert_eq!(url.as_str(), "otpauth://totp/Github:mock%40example.com?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Github&algorithm=SHA256&digits=6&period=1");
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn url_for_secret_matches_sha512() {
+        let totp = TOTP::new(
+            Algorithm::SHA512,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        let url = totp.get_url();
+        assert_eq!(url.as_str(), "otpauth://totp/Github:mock%40example.com?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Github&algorithm=SHA512&digits=6&period=1");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_set() {
+        use std::collections::HashSet;
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let mut other = totp.clone();
+        other.digits = Digits::Eight;
+        let mut set = HashSet::new();
+        assert!(set.insert(totp.clone()));
+        assert!(!set.insert(totp.clone()));
+        assert!(set.insert(other));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn debug_redacts_secret() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        let debug = format!("{:?}", totp);
+        assert!(debug.contains("secret: [redacted]"));
+        assert!(debug.contains("mock@example.com"));
+        assert!(!debug.contains(&format!("{:?}", totp.secret)));
+        assert!(!debug.contains(&totp.to_secret_base32()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_secret_base32() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        let json = serde_json::to_string(&totp).unwrap();
+        assert!(
+            json.contains(r#""secret":"KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ""#)
+        );
+        let decoded: TOTP = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.secret, totp.secret);
+        assert_eq!(decoded.get_url(), totp.get_url());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_validates() {
+        let json = r#"{"algorithm":"SHA1","digits":3,"skew":1,"step":30,"secret":"KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ","account_name":"mock@example.com","issuer":"Github"}"#;
+        let err = serde_json::from_str::<TOTP>(json).unwrap_err();
+        assert!(err.to_string().contains("3 digits is not allowed"));
+
+        let json = r#"{"algorithm":"SHA1","digits":6,"skew":1,"step":30,"secret":"KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ","account_name":"mock@example.com","issuer":"Github:"}"#;
+        let err = serde_json::from_str::<TOTP>(json).unwrap_err();
+        assert!(err.to_string().contains("must not contain a colon"));
+
+        let json = r#"{"algorithm":"SHA1","digits":6,"skew":1,"step":30,"secret":"KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ","account_name":"mock@example.com","issuer":"Github"}"#;
+        assert!(serde_json::from_str::<TOTP>(json).is_ok());
+    }
+
+    #[test]
+    fn display_url() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        assert_eq!(totp.to_string(), totp.get_url());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn ttl_ok() {
+        let totp = TOTP::new(
+            Algorithm::SHA512,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        assert!(totp.ttl().is_ok());
+    }
+
+    #[test]
+    fn returns_base32() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            totp.to_secret_base32().as_str(),
+            "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
+        );
+    }
+
+    #[test]
+    fn digits_conversion() {
+        for value in 6..=8 {
+            let digits = Digits::try_from(value).unwrap();
+            assert_eq!(usize::from(digits), value);
+            assert_eq!(digits, value);
+            assert_eq!(digits.to_string(), value.to_string());
+        }
+        for value in [0, 5, 9, usize::MAX] {
+            assert!(matches!(
+                Digits::try_from(value).unwrap_err(),
+                Error::InvalidDigits(v) if v == value
+            ));
+        }
+        assert_eq!(Digits::default(), Digits::Six);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn with_parameters() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap()
+        .with_step(60)
+        .and_then(|totp| totp.with_digits(8))
+        .and_then(|totp| totp.with_algorithm(Algorithm::SHA256).with_skew(2))
+        .unwrap();
+        assert_eq!(totp.step, 60);
+        assert_eq!(totp.digits, 8);
+        assert_eq!(totp.algorithm, Algorithm::SHA256);
+        assert_eq!(totp.skew, 2);
+        let sha512 = totp.with_algorithm(Algorithm::SHA512);
+        assert_eq!(sha512.algorithm, Algorithm::SHA512);
+        assert_eq!(sha512.with_algorithm(Algorithm::SHA256), totp);
+        assert!(sha512.get_url().contains("&algorithm=SHA512&"));
+        assert!(matches!(
+            totp.clone().with_step(0).unwrap_err(),
+            Error::InvalidStep(0)
+        ));
+        assert!(matches!(
+            totp.with_digits(9).unwrap_err(),
+            Error::InvalidDigits(9)
+        ));
+    }
+
+    #[test]
+    fn skew_too_large() {
+        let err = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            MAX_SKEW + 1,
+            30,
+            vec![0; 20],
+            String::new(),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::SkewTooLarge(11)));
+        assert_eq!(
+            err.to_string(),
+            "The skew must not be more than 10 steps; 11 is not allowed"
+        );
+
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            MAX_SKEW,
+            30,
+            vec![0; 20],
+            String::new(),
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            totp.clone().with_skew(255).unwrap_err(),
+            Error::SkewTooLarge(255)
+        ));
+        let totp = totp.with_skew_unchecked(255);
+        assert_eq!(totp.skew, 255);
+        assert!(totp.verify(&totp.generate(1000).unwrap(), 1000 + 255 * 30));
+    }
+
+    #[test]
+    fn asymmetric_skew_too_large() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            vec![0; 20],
+            String::new(),
+            None,
+        )
+        .unwrap();
+        let wide = Skew {
+            before: 255,
+            after: 255,
+        };
+        assert!(matches!(
+            totp.clone().with_asymmetric_skew(Some(wide)).unwrap_err(),
+            Error::SkewTooLarge(255)
+        ));
+        assert!(matches!(
+            totp.clone()
+                .with_asymmetric_skew(Some(Skew {
+                    before: 0,
+                    after: MAX_SKEW + 1,
+                }))
+                .unwrap_err(),
+            Error::SkewTooLarge(11)
+        ));
+        let capped = totp
+            .clone()
+            .with_asymmetric_skew(Some(Skew {
+                before: MAX_SKEW,
+                after: 0,
+            }))
+            .unwrap();
+        assert_eq!(capped.asymmetric_skew().unwrap().before, MAX_SKEW);
+        assert!(capped
+            .with_asymmetric_skew(None)
+            .unwrap()
+            .asymmetric_skew()
+            .is_none());
+
+        let totp = totp.with_asymmetric_skew_unchecked(Some(wide));
+        assert_eq!(totp.asymmetric_skew(), Some(wide));
+        assert!(totp.verify(&totp.generate(1000).unwrap(), 1000 + 255 * 30));
+    }
+
+    #[test]
+    fn unchecked_skew_round_trip() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        let skew = totp.clone().with_skew_unchecked(20);
+        let asymmetric = totp.with_asymmetric_skew_unchecked(Some(Skew {
+            before: 20,
+            after: 1,
+        }));
+
+        for totp in [skew, asymmetric] {
+            assert_eq!(TOTP::from_bytes(&totp.to_bytes()).unwrap(), totp);
+            assert_eq!(
+                TOTP::from_env_string(&totp.to_env_string()).unwrap(),
+                totp
+            );
+        }
+
+        // Without the marker the cap still applies.
+        let mut bytes = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap()
+        .to_bytes();
+        bytes[3] = 20;
+        assert!(matches!(
+            TOTP::from_bytes(&bytes),
+            Err(Error::SkewTooLarge(20))
+        ));
+        assert!(matches!(
+            TOTP::from_env_string("TOTP_v2:SHA1:6:20:30:KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ:mock::0::"),
+            Err(Error::SkewTooLarge(20))
+        ));
+        assert!(matches!(
+            TOTP::from_env_string("TOTP_v2:SHA1:6:1:30:KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ:mock::0::skew=20-1"),
+            Err(Error::SkewTooLarge(20))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_unchecked_skew_round_trip() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        let json = serde_json::to_string(&totp).unwrap();
+        assert!(!json.contains("unchecked_skew"));
+
+        let totp = totp.with_skew_unchecked(20);
+        let json = serde_json::to_string(&totp).unwrap();
+        assert!(json.contains(r#""unchecked_skew":true"#));
+        let decoded: TOTP = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, totp);
+
+        let json = json.replace(r#","unchecked_skew":true"#, "");
+        let err = serde_json::from_str::<TOTP>(&json).unwrap_err();
+        assert!(err.to_string().contains("20 is not allowed"));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn config() {
+        let totp = TOTP::new(
+            Algorithm::SHA256,
+            8,
+            2,
+            60,
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        let config = totp.config();
+        assert_eq!(
+            config,
+            TotpConfig {
+                algorithm: Algorithm::SHA256,
+                digits: Digits::Eight,
+                skew: 2,
+                step: 60,
+            }
+        );
+        let copy = config;
+        assert_eq!(
+            TOTP::from_config(
+                copy,
+                "TestSecretSuperSecret".as_bytes(),
+                "mock@example.com".to_string(),
+                Some("Github".to_string()),
+            )
+            .unwrap(),
+            totp
+        );
+        assert!(matches!(
+            TOTP::from_config(
+                TotpConfig { step: 0, ..config },
+                "TestSecretSuperSecret".as_bytes(),
+                String::new(),
+                None,
+            ),
+            Err(Error::InvalidStep(0))
+        ));
+        assert!(!format!("{:?}", config).contains("secret"));
+    }
+
+    #[test]
+    fn new_invalid_step() {
+        let err = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            0,
+            vec![0; 20],
+            String::new(),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidStep(0)));
+        let err = TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&period=0").unwrap_err();
+        assert!(
+            matches!(err, Error::UrlImport(e) if matches!(*e, Error::InvalidStep(0)))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn secret_strength() {
+        let mut totp =
+            TOTP::new_default(vec![0; 20], String::new(), None).unwrap();
+        assert_eq!(totp.secret_strength(), SecretStrength::Recommended);
+        assert_eq!(totp.secret_bits(), 160);
+        totp.algorithm = Algorithm::SHA256;
+        assert_eq!(totp.secret_strength(), SecretStrength::Minimum);
+        totp.secret = vec![0; 32];
+        assert_eq!(totp.secret_strength(), SecretStrength::Recommended);
+        totp.secret = vec![0; 15];
+        assert_eq!(totp.secret_strength(), SecretStrength::BelowMinimum);
+    }
+
+    #[test]
+    fn new_default() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        assert_eq!(totp.algorithm, Algorithm::SHA1);
+        assert_eq!(totp.digits, 6);
+        assert_eq!(totp.skew, 1);
+        assert_eq!(totp.step, 30);
+        assert!(matches!(
+            TOTP::new_default(vec![0; 8], String::new(), None).unwrap_err(),
+            Error::SecretTooSmall(64)
+        ));
+    }
+
+    #[test]
+    fn new_from_slice_and_array() {
+        let vec = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            String::new(),
+            None,
+        )
+        .unwrap();
+        let slice = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            String::new(),
+            None,
+        )
+        .unwrap();
+        let array =
+            TOTP::new_default(b"TestSecretSuperSecret", String::new(), None)
+                .unwrap();
+        assert_eq!(vec, slice);
+        assert_eq!(vec, array);
+    }
+
+    #[test]
+    fn generate_token() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.generate(1000).unwrap().as_str(), "659761");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn generate_token_current() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert_eq!(
+            totp.generate(time).unwrap().as_str(),
+            totp.generate_current().unwrap()
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn generates_and_checks_at() {
+        let totp = rfc6238(RFC6238_SEED_SHA1, Algorithm::SHA1);
+        let time = DateTime::from_timestamp_millis(59_999).unwrap();
+        assert_eq!(totp.generate_at(time).unwrap(), "94287082");
+        assert!(totp.check_at("94287082", time).unwrap());
+        assert!(!totp
+            .check_at("94287082", time + chrono::Duration::seconds(90))
+            .unwrap());
+
+        let time = DateTime::from_timestamp(1111111109, 0).unwrap();
+        assert_eq!(totp.generate_at(time).unwrap(), "07081804");
+
+        let before = DateTime::from_timestamp(-1, 0).unwrap();
+        assert!(matches!(
+            totp.generate_at(before),
+            Err(Error::Timestamp(-1))
+        ));
+        assert!(matches!(
+            totp.check_at("94287082", before),
+            Err(Error::Timestamp(-1))
+        ));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn generates_and_checks_at_offset() {
+        let totp = rfc6238(RFC6238_SEED_SHA1, Algorithm::SHA1);
+        let at = OffsetDateTime::from_unix_timestamp(59)
+            .unwrap()
+            .to_offset(time::UtcOffset::from_hms(2, 0, 0).unwrap());
+        assert_eq!(totp.generate_at_offset(at).unwrap(), "94287082");
+        assert!(totp.check_at_offset("94287082", at).unwrap());
+        assert!(!totp
+            .check_at_offset("94287082", at + time::Duration::seconds(90))
+            .unwrap());
+
+        // Before 1970 is an error rather than time 0.
+        let before = OffsetDateTime::from_unix_timestamp(-1).unwrap();
+        assert!(matches!(
+            totp.generate_at_offset(before),
+            Err(Error::Timestamp(-1))
+        ));
+        assert!(matches!(
+            totp.check_at_offset("94287082", before),
+            Err(Error::Timestamp(-1))
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn generate_token_at_system_time_or_zero() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            totp.generate_at_system_time_or_zero().unwrap(),
+            totp.generate_current().unwrap()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn generate_token_current_with_ttl() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let (token, ttl) = totp.generate_current_with_ttl().unwrap();
+        let time = totp.next_step_current().unwrap() - ttl;
+        assert_eq!(token, totp.generate(time).unwrap());
+        assert!((1..=30).contains(&ttl));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn generates_token_sha256() {
+        let totp = TOTP::new(
+            Algorithm::SHA256,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.generate(1000).unwrap().as_str(), "076417");
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn generates_token_sha512() {
+        let totp = TOTP::new(
+            Algorithm::SHA512,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.generate(1000).unwrap().as_str(), "473536");
+    }
+
+    #[test]
+    fn truncated_matches_generate() {
+        // Truncated values from rfc-4226 appendix D for counters 0 and 1.
+        let totp = rfc6238(RFC6238_SEED_SHA1, Algorithm::SHA1);
+        assert_eq!(totp.truncated(0).unwrap(), 1284755224);
+        assert_eq!(totp.truncated(30).unwrap(), 1094287082);
+        assert_eq!(
+            format!("{:08}", totp.truncated(30).unwrap() % 100_000_000),
+            totp.generate(30).unwrap()
+        );
+    }
+
+    #[test]
+    fn generate_at_counter_rfc4226() {
+        // HOTP values from rfc-4226 appendix D for counters 0 to 9.
+        let tokens = [
+            "755224", "287082", "359152", "969429", "338314", "254676",
+            "287922", "162583", "399871", "520489",
+        ];
+        let mut totp = rfc6238(RFC6238_SEED_SHA1, Algorithm::SHA1);
+        totp.digits = Digits::Six;
+        for (counter, token) in tokens.iter().enumerate() {
+            assert_eq!(
+                totp.generate_at_counter(counter as u64).unwrap(),
+                *token
+            );
+        }
+    }
+
+    #[test]
+    fn generate_numeric() {
+        let totp = rfc6238(RFC6238_SEED_SHA1, Algorithm::SHA1);
+        for (time, token, _, _) in RFC6238_VECTORS {
+            assert_eq!(
+                totp.generate_numeric(time).unwrap(),
+                token.parse::<u32>().unwrap()
+            );
+        }
+        // Leading zero is dropped.
+        assert_eq!(totp.generate_numeric(1111111109).unwrap(), 7081804);
+    }
+
+    #[test]
+    fn ttl_at_boundaries() {
+        let mut totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.ttl_at(0), 30);
+        assert_eq!(totp.ttl_at(29), 1);
+        assert_eq!(totp.ttl_at(30), 30);
+        assert_eq!(totp.next_step(0), 30);
+        assert_eq!(totp.next_step(29), 30);
+        assert_eq!(totp.next_step(30), 60);
+
+        totp.t0 = 100;
+        assert_eq!(totp.ttl_at(100), 30);
+        assert_eq!(totp.ttl_at(129), 1);
+        assert_eq!(totp.ttl_at(130), 30);
+        assert_eq!(totp.ttl_at(0), 130);
+        assert_eq!(totp.next_step(129), 130);
+        assert_eq!(totp.next_step(130), 160);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn truncated_matches_signature() {
+        for (seed, algorithm) in [
+            (RFC6238_SEED_SHA1, Algorithm::SHA1),
+            (RFC6238_SEED_SHA256, Algorithm::SHA256),
+            (RFC6238_SEED_SHA512, Algorithm::SHA512),
+        ] {
+            let totp = rfc6238(seed, algorithm);
+            for time in [59, 1111111109, 2000000000] {
+                assert_eq!(
+                    totp.truncated(time).unwrap(),
+                    truncate(&totp.sign(time).unwrap())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn generate_into() {
+        let mut totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let mut buffer = [0u8; 16];
+        for digits in [6, 7, 8] {
+            totp = totp.with_digits(digits).unwrap();
+            for time in [0, 1000, 59, 1111111109] {
+                let len = totp.generate_into(time, &mut buffer).unwrap();
+                assert_eq!(len, digits);
+                assert_eq!(
+                    &buffer[..len],
+                    totp.generate(time).unwrap().as_bytes()
+                );
+            }
+        }
+
+        let totp = totp.with_steam(true);
+        let len = totp.generate_into(1000, &mut buffer).unwrap();
+        assert_eq!(len, 5);
+        assert_eq!(&buffer[..len], totp.generate(1000).unwrap().as_bytes());
+
+        let totp = totp.with_steam(false);
+        assert!(matches!(
+            totp.generate_into(1000, &mut [0u8; 7]),
+            Err(Error::BufferTooSmall(8))
+        ));
+    }
+
+    #[test]
+    fn sign_returns_secret() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let signature = totp.sign(1000).unwrap();
+        assert_eq!(signature.len(), 20);
+        assert_eq!(format!("{:?}", signature), "Secret([redacted])");
+        assert_eq!(signature.clone().into_inner(), signature.to_vec());
+    }
+
+    #[test]
+    fn generates_token_with_color() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let other = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "OtherSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let (token, color) = totp.generate_with_color(1000).unwrap();
+        assert_eq!(token.as_str(), "659761");
+        assert_eq!(totp.generate_with_color(1000).unwrap(), (token, color));
+        assert_ne!(other.generate_with_color(1000).unwrap().1, color);
+    }
+
+    #[test]
+    fn verifies_any() {
+        let primary = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let backup = TOTP::new_default(
+            "OtherSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let totps = [primary.clone(), backup.clone()];
+        let token = backup.generate(1000).unwrap();
+        assert_eq!(verify_any(&totps, &token, 1000), Some(&backup));
+        let token = primary.generate(1000).unwrap();
+        assert_eq!(verify_any(&totps, &token, 1000), Some(&primary));
+        assert_eq!(verify_any(&totps, "000000", 5000), None);
+    }
+
+    #[test]
+    fn codes_equal() {
+        assert!(super::codes_equal("123456", "123456"));
+        assert!(!super::codes_equal("123456", "123457"));
+        assert!(!super::codes_equal("123456", "1234567"));
+        assert!(!super::codes_equal("123456", ""));
+        assert!(super::codes_equal("", ""));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn generates_multi() {
+        let sha1 = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let sha256 = TOTP::new(
+            Algorithm::SHA256,
+            8,
+            1,
+            30,
+            "OtherSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let tokens =
+            generate_multi(&[sha1.clone(), sha256.clone()], 1000).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                sha1.generate(1000).unwrap(),
+                sha256.generate(1000).unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn rfc6238_vectors_sha1() {
+        let totp = rfc6238(RFC6238_SEED_SHA1, Algorithm::SHA1);
+        for (time, token, _, _) in RFC6238_VECTORS {
+            assert_eq!(totp.generate(time).unwrap().as_str(), token);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn rfc6238_vectors_sha256() {
+        let totp = rfc6238(RFC6238_SEED_SHA256, Algorithm::SHA256);
+        for (time, _, token, _) in RFC6238_VECTORS {
+            assert_eq!(totp.generate(time).unwrap().as_str(), token);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn rfc6238_vectors_sha512() {
+        let totp = rfc6238(RFC6238_SEED_SHA512, Algorithm::SHA512);
+        for (time, _, _, token) in RFC6238_VECTORS {
+            assert_eq!(totp.generate(time).unwrap().as_str(), token);
+        }
+    }
+
+    #[test]
+    fn checks_token() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            0,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert!(totp.verify("659761", 1000));
+        #[allow(deprecated)]
+        let checked = totp.check("659761", 1000);
+        assert!(checked);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn checks_token_current() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            0,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert!(totp
+            .verify_current(&totp.generate_current().unwrap())
+            .unwrap());
+        assert!(!totp.verify_current("bogus").unwrap());
+        #[allow(deprecated)]
+        let checked = totp.check_current("bogus").unwrap();
+        assert!(!checked);
+    }
+
+    #[test]
+    fn checks_token_malformed() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let calls = core::cell::Cell::new(0);
+        let generate = |r| {
+            calls.set(calls.get() + 1);
+            totp.encode_token(r)
+        };
+        assert!(!totp.check_token(b"12", 1000, generate));
+        assert!(!totp.check_token(b"abcdef123", 1000, generate));
+        assert!(!totp.check_token(b"65976a", 1000, generate));
+        assert_eq!(calls.get(), 0);
+        assert!(totp.check_token(b"659761", 1000, generate));
+        assert!(calls.get() > 0);
+        assert!(!totp.verify("6597610", 1000));
+        assert_eq!(totp.check_window("12", 1000, 1, 1), None);
+    }
+
+    #[test]
+    fn checks_token_with_skew() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert!(
+            totp.verify("174269", 1000)
+                && totp.verify("659761", 1000)
+                && totp.verify("260393", 1000)
+        );
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn verifies_exactly_the_skew_window(
+            secret in proptest::collection::vec(
+                proptest::num::u8::ANY,
+                16..65,
+            ),
+            algorithm in 0..ALGORITHMS.len(),
+            digits in 6usize..=8,
+            step in 1u64..=120,
+            skew in 0..=MAX_SKEW,
+            // Timestamps near 0 check the window is clamped at the
+            // first step.
+            time in proptest::prop_oneof![0u64..360, 0u64..1 << 40],
+            offset in 0u64..120,
+        ) {
+            let totp = TOTP::new(
+                ALGORITHMS[algorithm],
+                digits,
+                skew,
+                step,
+                secret,
+                String::new(),
+                None,
+            )
+            .unwrap();
+            let step_k = totp.counter(time);
+            let token = totp.generate(time).unwrap();
+
+            let skew = skew as u64;
+            let first = step_k.saturating_sub(skew + 2);
+            for counter in first..=step_k + skew + 2 {
+                let at = totp.step_time(counter) + offset % step;
+                // Another step in the window may have the same token
+                // by chance.
+                let window = counter.saturating_sub(skew)..=counter + skew;
+                let matched = window.rev().find(|k| {
+                    totp.generate_at_counter(*k).unwrap() == token
+                });
+                if counter.abs_diff(step_k) <= skew {
+                    proptest::prop_assert!(matched.is_some());
+                }
+                proptest::prop_assert_eq!(
+                    totp.verify(&token, at),
+                    matched.is_some(),
+                    "{:?} generated at {} checked at {}",
+                    totp,
+                    time,
+                    at
+                );
+                proptest::prop_assert_eq!(
+                    totp.check_returning_counter(&token, at),
+                    matched
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn generates_token_steam() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Steam".to_string()),
+        )
+        .unwrap();
+        assert_eq!(totp.generate_steam(1000).unwrap().as_str(), "RBJNV");
+    }
+
+    #[test]
+    fn verifies_token_bytes() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            0,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert!(totp.verify_bytes(b"659761", 1000));
+        assert!(!totp.verify_bytes(b"65976\xff", 1000));
+        assert!(!totp.verify_bytes(&[0xff; 6], 1000));
+    }
+
+    #[test]
+    fn checks_token_with_asymmetric_skew() {
+        let mut totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        totp = totp
+            .with_asymmetric_skew(Some(Skew {
+                before: 2,
+                after: 0,
+            }))
+            .unwrap();
+        let token = totp.generate(1000).unwrap();
+        assert!(totp.verify(&token, 1000));
+        assert!(totp.verify(&token, 1030));
+        assert!(totp.verify(&token, 1060));
+        assert!(!totp.verify(&token, 1090));
+        assert!(!totp.verify(&token, 960));
+    }
+
+    #[test]
+    fn checks_token_steam_with_skew() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Steam".to_string()),
+        )
+        .unwrap();
+        assert!(
+            totp.check_steam("W5MK4", 1000)
+                && totp.check_steam("HG3Y6", 1000)
+                && totp.check_steam("3VYKF", 1000)
+        );
+        assert!(!totp.check_steam("659761", 1000));
+    }
+
+    #[test]
+    fn checks_and_consumes_token() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let mut last_used_step = None;
+        assert!(totp.check_and_consume("659761", 1000, &mut last_used_step));
+        assert_eq!(last_used_step, Some(1000));
+        assert!(!totp.check_and_consume("659761", 1000, &mut last_used_step));
+        assert!(!totp.check_and_consume("174269", 1000, &mut last_used_step));
+        assert!(totp.check_and_consume("260393", 1000, &mut last_used_step));
+        assert_eq!(last_used_step, Some(1001));
+        assert!(!totp.check_and_consume("bogus", 1001, &mut last_used_step));
+        assert_eq!(last_used_step, Some(1001));
+    }
+
+    #[test]
+    fn checks_returning_counter() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.check_returning_counter("659761", 1000), Some(1000));
+        assert_eq!(totp.check_returning_counter("659761", 1001), Some(1000));
+        assert_eq!(totp.check_returning_counter("260393", 1000), Some(1001));
+        assert_eq!(totp.check_returning_counter("659761", 1002), None);
+        assert_eq!(totp.check_returning_counter("bogus", 1000), None);
+
+        let totp = totp.with_step(30).unwrap();
+        let token = totp.generate(1_000_000).unwrap();
+        assert_eq!(
+            totp.check_returning_counter(&token, 1_000_000),
+            Some(1_000_000 / 30)
+        );
+    }
+
+    #[test]
+    fn checks_strict() {
+        let mut totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        // Previous, current and next step at 1000.
+        assert!(totp.check_strict("174269", 1000));
+        assert!(totp.check_strict("659761", 1000));
+        assert!(!totp.check_strict("260393", 1000));
+        assert!(totp.verify("260393", 1000));
+        assert!(!totp.check_strict("bogus", 1000));
+
+        totp = totp
+            .with_asymmetric_skew(Some(Skew {
+                before: 0,
+                after: 2,
+            }))
+            .unwrap();
+        assert!(totp.check_strict("659761", 1000));
+        assert!(!totp.check_strict("174269", 1000));
+        assert!(!totp.check_strict("260393", 1000));
+    }
+
+    #[test]
+    fn checks_token_window() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            0,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.check_window("659761", 1000, 0, 0), Some(0));
+        assert_eq!(totp.check_window("174269", 1000, 3, 3), Some(-1));
+        assert_eq!(totp.check_window("260393", 1000, 3, 3), Some(1));
+        assert_eq!(totp.check_window("659761", 1003, 3, 0), Some(-3));
+        assert_eq!(totp.check_window("659761", 1004, 3, 3), None);
+        assert_eq!(totp.check_window("659761", 997, 0, 2), None);
+        assert_eq!(totp.check_window("659761", 0, 5, 1000), Some(1000));
+    }
+
+    #[test]
+    fn resync() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let time = 1_000_000;
+        // Token 50 steps behind and 40 steps ahead of the server.
+        let behind = totp.generate(time - 50 * 30).unwrap();
+        let ahead = totp.generate(time + 40 * 30).unwrap();
+        assert!(!totp.verify(&behind, time));
+        assert_eq!(totp.resync(&behind, time, 100), Some(-50));
+        assert_eq!(totp.resync(&ahead, time, 100), Some(40));
+        assert_eq!(totp.resync(&behind, time, 10), None);
+        assert_eq!(totp.resync("bogus", time, 100), None);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn verifies_with_algorithm_report() {
+        let totp = TOTP::new(
+            Algorithm::SHA256,
+            6,
+            0,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            totp.verify_with_algorithm_report("076417", 1000, &[]),
+            Some(Algorithm::SHA256)
+        );
+        assert_eq!(
+            totp.verify_with_algorithm_report("659761", 1000, &[]),
+            None
+        );
+        assert_eq!(
+            totp.verify_with_algorithm_report(
+                "659761",
+                1000,
+                &[Algorithm::SHA512, Algorithm::SHA1]
+            ),
+            Some(Algorithm::SHA1)
+        );
+    }
+
+    #[test]
+    fn counter() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.counter(0), 0);
+        assert_eq!(totp.counter(29), 0);
+        assert_eq!(totp.counter(30), 1);
+        assert_eq!(totp.counter(1000), 33);
+    }
+
+    #[test]
+    fn zero_step_field() {
+        let mut totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let token = totp.generate(1000).unwrap();
+        totp.step = 0;
+        assert_eq!(totp.counter(1000), 0);
+        assert!(matches!(totp.generate(1000), Err(Error::InvalidStep(0))));
+        assert!(matches!(totp.sign(1000), Err(Error::InvalidStep(0))));
+        assert!(!totp.verify(&token, 1000));
+        assert_eq!(totp.check_window(&token, 1000, 1, 1), None);
+        assert!(totp.valid_codes(1000).is_err());
+        assert!(totp.codes_from(1000).next().unwrap().is_err());
+        assert_eq!(totp.next_step(1000), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn counter_current() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert!(totp.counter_current().unwrap() >= totp.counter(time));
+    }
+
+    #[test]
+    fn codes_from() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let codes: Vec<(u64, String)> = totp
+            .codes_from(1000)
+            .take(3)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(
+            codes,
+            vec![
+                (990, totp.generate(990).unwrap()),
+                (1020, totp.generate(1020).unwrap()),
+                (1050, totp.generate(1050).unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn codes_from_last_step() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.codes_from(u64::MAX).take(2).count(), 1);
+    }
+
+    #[test]
+    fn t0_offset() {
+        let mut totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let token = totp.generate(1000).unwrap();
+        totp.t0 = 10;
+        assert_eq!(totp.counter(1010), 33);
+        assert_eq!(totp.generate(1010).unwrap(), token);
+        assert_eq!(totp.next_step(1010), 1030);
+        assert_eq!(totp.next_step(5), 40);
+        assert!(totp.verify(&token, 1010));
+        assert_eq!(
+            totp.codes_from(1010).next().unwrap().unwrap(),
+            (1000, token)
+        );
+    }
+
+    #[test]
+    fn t0_url_round_trip() {
+        let url = "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=GitHub&algorithm=SHA1&digits=6&period=30&t0=1000";
+        let totp = TOTP::from_url(url).unwrap();
+        assert_eq!(totp.t0, 1000);
+        assert_eq!(totp.get_url().as_str(), url);
+        let totp = TOTP::from_url(
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&epoch=60",
+        )
+        .unwrap();
+        assert_eq!(totp.t0, 60);
+        assert!(matches!(
+            TOTP::from_url(
+                "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&t0=-1",
+            ),
+            Err(Error::T0(_))
+        ));
+    }
+
+    #[test]
+    fn t0_overflow() {
+        let totp = TOTP::from_url(
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&epoch=18446744073709551615",
+        )
+        .unwrap();
+        assert_eq!(totp.t0, u64::MAX);
+        assert_eq!(totp.counter(1000), 0);
+        assert_eq!(totp.next_step(1000), u64::MAX);
+        assert_eq!(totp.ttl_at(1000), u64::MAX - 1000);
+        assert_eq!(totp.step_bounds(1000), (u64::MAX, u64::MAX));
+        assert_eq!(totp.next_step(u64::MAX), u64::MAX);
+        let token = totp.generate(1000).unwrap();
+        assert_eq!(
+            totp.check_with_expiry(&token, 1000),
+            Some(u64::MAX - 1000)
+        );
+        assert_eq!(
+            totp.codes_from(1000).nth(2).unwrap().unwrap().0,
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn image_url_round_trip() {
+        let url = "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=GitHub&algorithm=SHA1&digits=6&period=30&image=https%3A%2F%2Fexample.com%2Ficon.png";
+        let totp = TOTP::from_url(url).unwrap();
+        assert_eq!(
+            totp.image.as_deref(),
+            Some("https://example.com/icon.png")
+        );
+        assert_eq!(totp.get_url().as_str(), url);
+        assert_eq!(TOTP::from_url(totp.get_url()).unwrap().image, totp.image);
+    }
+
+    #[test]
+    fn next_step() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            Some("Mock Service".to_string()),
+        )
+        .unwrap();
+        assert!(totp.next_step(0) == 30);
+        assert!(totp.next_step(29) == 30);
+        assert!(totp.next_step(30) == 60);
+    }
+
+    #[test]
+    fn little_endian_counter() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.endianness(), Endianness::Big);
+        let little = totp.clone().with_endianness(Endianness::Little);
+        assert_ne!(little, totp);
+
+        let counter = totp.counter(1000);
+        let signature = Algorithm::SHA1
+            .sign(&totp.secret, &counter.to_le_bytes())
+            .unwrap();
+        assert_eq!(*little.sign(1000).unwrap(), *signature);
+        let token = little.generate(1000).unwrap();
+        assert_eq!(token, format!("{:06}", truncate(&signature) % 1_000_000));
+        assert_ne!(token, totp.generate(1000).unwrap());
+        assert!(little.verify(&token, 1000));
+        assert!(!totp.verify(&token, 1000));
+        assert_eq!(little.valid_codes(1000).unwrap()[1], token);
+    }
+
+    #[test]
+    fn step_bounds() {
+        let mut totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.step_bounds(0), (0, 30));
+        assert_eq!(totp.step_bounds(29), (0, 30));
+        assert_eq!(totp.step_bounds(30), (30, 60));
+        totp.t0 = 10;
+        assert_eq!(totp.step_bounds(45), (40, 70));
+        assert_eq!(totp.step_bounds(5), (10, 40));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn current_step_bounds() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let (start, end) = totp.current_step_bounds().unwrap();
+        let now = system_time().unwrap();
+        assert_eq!(end - start, 30);
+        assert_eq!(start % 30, 0);
+        assert!(start <= now && now < end + 30);
+    }
+
+    #[test]
+    #[cfg(not(feature = "sha2"))]
+    fn sha2_disabled() {
+        assert!(matches!(
+            "SHA256".parse::<Algorithm>(),
+            Err(Error::Algorithm(_))
+        ));
+        assert!(matches!(
+            TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&algorithm=SHA512"),
+            Err(Error::Algorithm(_))
+        ));
+        let mut bytes = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap()
+        .to_bytes();
+        bytes[1] = 2;
+        assert!(matches!(TOTP::from_bytes(&bytes), Err(Error::Decode(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_url_err() {
+        assert!(TOTP::from_url("otpauth://hotp/123").is_err());
+        assert!(TOTP::from_url("otpauth://totp/GitHub:test").is_err());
+        assert!(TOTP::from_url(
+            "otpauth://totp/GitHub:test:?secret=ABC&digits=8&period=60&algorithm=SHA256"
+        )
+        .is_err());
+        assert!(TOTP::from_url("otpauth://totp/Github:mock%40example.com?issuer=GitHub&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&algorithm=SHA1").is_err())
+    }
+
+    #[test]
+    fn from_url_default() {
+        let totp = TOTP::from_url(
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+        )
+        .unwrap();
+        assert_eq!(
+            totp.secret,
+            base32::decode(
+                base32::Alphabet::Rfc4648 { padding: false },
+                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
+            )
+            .unwrap()
+        );
+        assert_eq!(totp.algorithm, Algorithm::SHA1);
+        assert_eq!(totp.digits, 6);
+        assert_eq!(totp.skew, 0);
+        assert_eq!(totp.step, 30);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_url_query() {
+        let totp = TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256").unwrap();
+        assert_eq!(
+            totp.secret,
+            base32::decode(
+                base32::Alphabet::Rfc4648 { padding: false },
+                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
+            )
+            .unwrap()
+        );
+        assert_eq!(totp.algorithm, Algorithm::SHA256);
+        assert_eq!(totp.digits, 8);
+        assert_eq!(totp.skew, 0);
+        assert_eq!(totp.step, 60);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_url_query_sha512() {
+        let totp = TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA512").unwrap();
+        assert_eq!(
+            totp.secret,
+            base32::decode(
+                base32::Alphabet::Rfc4648 { padding: false },
+                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
+            )
+            .unwrap()
+        );
+        assert_eq!(totp.algorithm, Algorithm::SHA512);
+        assert_eq!(totp.digits, 8);
+        assert_eq!(totp.skew, 0);
+        assert_eq!(totp.step, 60);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_secret_base32_full() {
+        let totp = TOTP::from_secret_base32_full(
+            "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            Algorithm::SHA256,
+            8,
+            2,
+            60,
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            totp,
+            TOTP::new(
+                Algorithm::SHA256,
+                8,
+                2,
+                60,
+                "TestSecretSuperSecret".as_bytes(),
+                "mock@example.com".to_string(),
+                Some("Github".to_string()),
+            )
+            .unwrap()
+        );
+        let err = TOTP::from_secret_base32_full(
+            "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "mock:example.com".to_string(),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::AccountName(_)));
+    }
+
+    #[test]
+    fn base32_crockford_round_trip() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let encoded = totp.to_secret_base32_with(base32::Alphabet::Crockford);
+        assert_eq!(encoded.as_str(), "AHJQ6X2KCNHQ4SBMADTQ0SBJADJP6WK5EG");
+        let decoded = TOTP::from_secret_base32_with(
+            &encoded,
+            base32::Alphabet::Crockford,
+        )
+        .unwrap();
+        assert_eq!(decoded.secret, totp.secret);
+        assert!(TOTP::from_secret_base32_with(
+            &encoded,
+            base32::Alphabet::Rfc4648 { padding: false }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn returns_hex() {
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            1,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            totp.to_secret_hex().as_str(),
+            "546573745365637265745375706572536563726574"
+        );
+    }
+
+    #[test]
+    fn from_secret_hex() {
+        let totp = TOTP::from_secret_hex(
+            "546573745365637265745375706572536563726574",
+        )
+        .unwrap();
+        assert_eq!(totp.secret, "TestSecretSuperSecret".as_bytes());
+        let upper = TOTP::from_secret_hex(
+            "546573745365637265745375706572536563726574".to_uppercase(),
+        )
+        .unwrap();
+        assert_eq!(upper.secret, totp.secret);
+    }
+
+    #[test]
+    fn from_secret_bytes() {
+        let totp = TOTP::from_secret_bytes(
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+        )
+        .unwrap();
+        assert_eq!(
+            totp,
+            TOTP::new_default(
+                "TestSecretSuperSecret".as_bytes(),
+                String::new(),
+                None
+            )
+            .unwrap()
+        );
+        assert!(matches!(
+            TOTP::from_secret_bytes(vec![0; 15]),
+            Err(Error::SecretTooSmall(120))
+        ));
+    }
+
+    #[test]
+    fn from_secret_hex_err() {
+        assert!(matches!(
+            TOTP::from_secret_hex(
+                "5465737453656372657453757065725365637265741"
+            ),
+            Err(Error::Hex(_))
+        ));
+        assert!(matches!(
+            TOTP::from_secret_hex(
+                "5465737453656372657453757065725365637265zz"
+            ),
+            Err(Error::Hex(_))
+        ));
+        assert!(matches!(
+            TOTP::from_secret_hex("+1+1737453656372657453757065725365637265"),
+            Err(Error::Hex(_))
+        ));
+    }
+
+    #[test]
+    fn from_secret_base32_spaced() {
+        let totp =
+            TOTP::from_secret_base32("KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ")
+                .unwrap();
+        let spaced = TOTP::from_secret_base32(
+            "krsx g5ct mvrx ezlu kn2x azls knsw g4tf oq",
+        )
+        .unwrap();
+        assert_eq!(spaced.secret, totp.secret);
+    }
+
+    #[test]
+    fn to_secret_base32_grouped() {
+        let totp =
+            TOTP::from_secret_base32("KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ")
+                .unwrap();
+        let grouped = totp.to_secret_base32_grouped();
+        assert_eq!(grouped, "KRSX G5CT MVRX EZLU KN2X AZLS KNSW G4TF OQ");
+        assert_eq!(
+            TOTP::from_secret_base32(&grouped).unwrap().secret,
+            totp.secret
+        );
+    }
+
+    #[test]
+    fn from_secret_base32_padded() {
+        let totp =
+            TOTP::from_secret_base32("KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ")
+                .unwrap();
+        let padded = TOTP::from_secret_base32(
+            "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ======",
+        )
+        .unwrap();
+        assert_eq!(padded.secret, totp.secret);
+    }
+
+    #[test]
+    fn from_secret_base32_off_length() {
+        // 35 and 33 characters leave 15 and 5 bits after the last byte.
+        for secret in [
+            "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQA",
+            "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFO",
+            "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQA=====",
+        ] {
+            assert!(matches!(
+                TOTP::from_secret_base32(secret),
+                Err(Error::SecretLength(_))
+            ));
+        }
+        assert!(matches!(
+            TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQA"),
+            Err(Error::SecretLength(35))
+        ));
+        assert!(matches!(
+            TOTP::from_secret_base32_with(
+                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQA",
+                base32::Alphabet::Crockford,
+            ),
+            Err(Error::SecretLength(35))
+        ));
+    }
+
+    #[test]
+    fn from_url_padded_secret() {
+        let totp = TOTP::from_url(
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ%3D%3D%3D%3D%3D%3D",
+        )
+        .unwrap();
+        assert_eq!(totp.secret, "TestSecretSuperSecret".as_bytes());
+        let totp = TOTP::from_url(
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ======",
+        )
+        .unwrap();
+        assert_eq!(totp.secret, "TestSecretSuperSecret".as_bytes());
+    }
+
+    #[test]
+    fn from_url_spaced_secret() {
+        let totp = TOTP::from_url(
+            "otpauth://totp/GitHub:test?secret=KRSX%20G5CT%20MVRX%20EZLU%20KN2X%20AZLS%20KNSW%20G4TF%20OQ",
+        )
+        .unwrap();
+        assert_eq!(totp.secret, "TestSecretSuperSecret".as_bytes());
+    }
+
+    #[test]
+    fn from_url_with_skew() {
+        let totp = TOTP::from_url_with_skew(
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            2,
         )
+        .unwrap();
+        assert_eq!(totp.skew, 2);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn from_secret_base32_with_skew() {
+        let totp =
+            TOTP::from_secret_base32("KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ")
+                .unwrap();
+        assert_eq!(totp.skew, 1);
+        let totp = TOTP::from_secret_base32_with_skew(
+            "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            0,
+        )
+        .unwrap();
+        assert_eq!(totp.skew, 0);
+    }
 
     #[test]
-    fn new_wrong_issuer() {
-        let totp = TOTP::new(
+    fn from_url_to_url() {
+        let totp = TOTP::from_url("otpauth://totp/Github:mock%40example.com?issuer=Github&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&algorithm=SHA1").unwrap();
+        let totp_bis = TOTP::new(
             Algorithm::SHA1,
             6,
             1,
-            1,
+            30,
             "TestSecretSuperSecret".as_bytes().to_vec(),
             "mock@example.com".to_string(),
-            Some("Github:".to_string()),
-        );
-        assert!(totp.is_err());
-        assert!(matches!(totp.unwrap_err(), Error::Issuer(_)));
+            Some("Github".to_string()),
+        )
+        .unwrap();
+        assert_eq!(totp.get_url(), totp_bis.get_url());
+        assert!(totp.same_provisioning(&totp_bis));
+        assert_ne!(totp, totp_bis);
     }
 
     #[test]
-    fn new_wrong_account_name() {
-        let totp = TOTP::new(
-            Algorithm::SHA1,
-            6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
-            "mock:example.com".to_string(),
+    fn same_provisioning() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
             Some("Github".to_string()),
-        );
-        assert!(totp.is_err());
-        assert!(matches!(totp.unwrap_err(), Error::AccountName(_)));
+        )
+        .unwrap();
+        let mut other = totp.clone().with_skew(3).unwrap();
+        assert!(totp.same_provisioning(&other));
+        other.secret = "OtherSecretSuperSecret".as_bytes().to_vec();
+        assert!(!totp.same_provisioning(&other));
+        let other = totp.clone().with_digits(8).unwrap();
+        assert!(!totp.same_provisioning(&other));
+        let mut other = totp.clone();
+        other.issuer = None;
+        assert!(!totp.same_provisioning(&other));
+        other = totp.clone();
+        other.t0 = 30;
+        assert!(!totp.same_provisioning(&other));
+        let other = totp.clone().with_steam(true);
+        assert!(!totp.same_provisioning(&other));
     }
 
     #[test]
-    fn new_wrong_account_name_no_issuer() {
+    fn check_with_expiry() {
         let totp = TOTP::new(
             Algorithm::SHA1,
             6,
             1,
-            1,
+            30,
             "TestSecretSuperSecret".as_bytes().to_vec(),
-            "mock:example.com".to_string(),
+            "mock@example.com".to_string(),
             None,
-        );
-        assert!(totp.is_err());
-        assert!(matches!(totp.unwrap_err(), Error::AccountName(_)));
+        )
+        .unwrap();
+        let token = totp.generate(1000).unwrap();
+        assert_eq!(totp.check_with_expiry(&token, 1000), Some(20));
+        assert_eq!(totp.check_with_expiry(&token, 1025), Some(0));
+        assert_eq!(totp.check_with_expiry(&token, 985), Some(35));
+        assert_eq!(totp.check_with_expiry(&token, 1060), None);
+        assert_eq!(totp.check_with_expiry("bogus", 1000), None);
     }
 
     #[test]
-    fn comparison_ok() {
-        let reference = TOTP::new(
-            Algorithm::SHA1,
-            6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
+    #[cfg(feature = "sha2")]
+    fn try_from_parsed_url() {
+        let url = Url::parse("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256").unwrap();
+        let totp = TOTP::try_from(url.clone()).unwrap();
+        assert_eq!(totp, TOTP::from_url(url.as_str()).unwrap());
+        assert_eq!(totp.skew, 0);
+
+        let url = Url::parse("http://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ").unwrap();
+        assert!(matches!(TOTP::try_from(url).unwrap_err(), Error::Scheme(_)));
+    }
+
+    #[test]
+    fn url_round_trip_digits_period() {
+        for digits in [6, 7, 8] {
+            for step in [30, 60] {
+                let totp = TOTP::new(
+                    Algorithm::SHA1,
+                    digits,
+                    0,
+                    step,
+                    "TestSecretSuperSecret".as_bytes(),
+                    "mock@example.com".to_string(),
+                    Some("Github".to_string()),
+                )
+                .unwrap();
+                let url = totp.get_url();
+                assert!(url.contains(&format!("&digits={}&", digits)));
+                assert!(url.ends_with(&format!("&period={}", step)));
+                let parsed = TOTP::from_url(&url).unwrap();
+                assert_eq!(parsed, totp);
+                assert_eq!(parsed.get_url(), url);
+            }
+        }
+    }
+
+    #[test]
+    fn url_empty_account_name() {
+        let mut totp =
+            TOTP::from_secret_base32("KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ")
+                .unwrap();
+        assert_eq!(totp.get_url(), "otpauth://totp/?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&algorithm=SHA1&digits=6&period=30");
+        assert_eq!(TOTP::from_url(totp.get_url()).unwrap().account_name, "");
+
+        totp.issuer = Some("Github".to_string());
+        assert_eq!(totp.get_url(), "otpauth://totp/Github:?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Github&algorithm=SHA1&digits=6&period=30");
+        let parsed = TOTP::from_url(totp.get_url()).unwrap();
+        assert_eq!(parsed.account_name, "");
+        assert_eq!(parsed.issuer.as_deref(), Some("Github"));
+    }
+
+    #[test]
+    fn set_account_name() {
+        let mut totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
             "mock@example.com".to_string(),
-            Some("Github".to_string()),
+            None,
         )
         .unwrap();
-        let test = TOTP::new(
+        totp.set_account_name("other@example.com".to_string())
+            .unwrap();
+        assert_eq!(totp.account_name, "other@example.com");
+        assert!(matches!(
+            totp.set_account_name("Github:mock".to_string()),
+            Err(Error::AccountName(_))
+        ));
+        assert_eq!(totp.account_name, "other@example.com");
+    }
+
+    #[test]
+    fn unchecked_label_round_trip() {
+        let totp = TOTP::new_unchecked_label(
             Algorithm::SHA1,
             6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
-            "mock@example.com".to_string(),
-            Some("Github".to_string()),
+            0,
+            30,
+            "TestSecretSuperSecret".as_bytes(),
+            "mock:user".to_string(),
+            Some("Legacy:Corp".to_string()),
         )
         .unwrap();
-        assert_eq!(reference, test);
+        let url = totp.get_url();
+        assert_eq!(
+            url,
+            "otpauth://totp/Legacy%3ACorp:mock%3Auser?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Legacy%3ACorp&algorithm=SHA1&digits=6&period=30"
+        );
+        assert_eq!(TOTP::from_url_unchecked_label(&url).unwrap(), totp);
+        assert!(matches!(
+            TOTP::from_url(&url),
+            Err(Error::UrlImport(e)) if matches!(*e, Error::AccountName(_))
+        ));
+        assert!(matches!(
+            TOTP::new_default(
+                "TestSecretSuperSecret".as_bytes(),
+                "mock".to_string(),
+                Some("Legacy:Corp".to_string()),
+            ),
+            Err(Error::Issuer(_))
+        ));
+
+        assert_eq!(TOTP::from_bytes(&totp.to_bytes()).unwrap(), totp);
+        let value = totp.to_env_string();
+        assert!(value.ends_with(":unchecked-label"));
+        assert_eq!(TOTP::from_env_string(&value).unwrap(), totp);
+
+        // Without the marker the colons are still rejected.
+        assert!(matches!(
+            TOTP::from_env_string(value.trim_end_matches("unchecked-label")),
+            Err(Error::AccountName(_))
+        ));
+        let mut bytes = totp.to_bytes();
+        let len = bytes.len();
+        bytes[len - 3] = 0;
+        assert!(matches!(
+            TOTP::from_bytes(&bytes),
+            Err(Error::AccountName(_))
+        ));
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn url_for_secret_matches_sha1_without_issuer() {
-        let totp = TOTP::new(
+    fn serde_unchecked_label_round_trip() {
+        let totp = TOTP::new_unchecked_label(
             Algorithm::SHA1,
             6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
-            "mock@example.com".to_string(),
-            None,
+            0,
+            30,
+            "TestSecretSuperSecret".as_bytes(),
+            "mock:user".to_string(),
+            Some("Legacy:Corp".to_string()),
         )
         .unwrap();
+        let json = serde_json::to_string(&totp).unwrap();
+        assert!(json.contains(r#""unchecked_label":true"#));
+        let decoded: TOTP = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, totp);
+
+        let json = json.replace(r#","unchecked_label":true"#, "");
+        let err = serde_json::from_str::<TOTP>(&json).unwrap_err();
+        assert!(err.to_string().contains("must not contain a colon"));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_url_unknown_param() {
+        let totp = TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256&foo=bar").unwrap();
+        assert_eq!(
+            totp.secret,
+            base32::decode(
+                base32::Alphabet::Rfc4648 { padding: false },
+                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
+            )
+            .unwrap()
+        );
+        assert_eq!(totp.algorithm, Algorithm::SHA256);
+        assert_eq!(totp.digits, 8);
+        assert_eq!(totp.skew, 0);
+        assert_eq!(totp.step, 60);
+    }
+
+    #[test]
+    fn from_url_verbose() {
+        let (totp, unsupported) = TOTP::from_url_verbose("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&foo=bar&digits=8&vendor=a%20b").unwrap();
+        assert_eq!(totp.digits, 8);
+        assert_eq!(
+            unsupported,
+            vec![
+                ("foo".to_string(), "bar".to_string()),
+                ("vendor".to_string(), "a b".to_string()),
+            ]
+        );
+        let (_, unsupported) = TOTP::from_url_verbose("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ").unwrap();
+        assert!(unsupported.is_empty());
+    }
+
+    #[test]
+    fn from_url_steam() {
+        let totp = TOTP::from_url("otpauth://totp/Steam:mock?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Steam&encoder=steam").unwrap();
+        assert!(totp.is_steam());
+        assert_eq!(totp.generate(1000).unwrap(), "RBJNV");
+        assert!(totp.verify("RBJNV", 1000));
+        assert!(!totp.verify("659761", 1000));
+
+        // The issuer alone does not switch on Steam mode.
+        let totp = TOTP::from_url("otpauth://totp/Steam:mock?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Steam").unwrap();
+        assert!(!totp.is_steam());
+        assert_eq!(totp.generate(1000).unwrap(), "804420");
+
+        let totp = TOTP::from_url("otpauth://steam/Steam:mock?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=5").unwrap();
+        assert!(totp.is_steam());
+        assert_eq!(totp.generate(1000).unwrap(), "RBJNV");
+
+        let totp = TOTP::from_url("otpauth://totp/GitHub:mock?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ").unwrap();
+        assert!(!totp.is_steam());
+        assert!(totp.with_steam(true).is_steam());
+
+        let totp = TOTP::from_url("otpauth://totp/Valve:mock?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&encoder=steam").unwrap();
+        assert!(totp.is_steam());
+        assert_eq!(totp.generate(1000).unwrap(), "RBJNV");
+    }
+
+    #[test]
+    fn steam_url_round_trip() {
+        let totp = TOTP::from_url("otpauth://totp/Valve:mock?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Valve")
+            .unwrap()
+            .with_steam(true);
         let url = totp.get_url();
-        assert_eq!(url.as_str(), "otpauth://totp/mock%40example.com?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&algorithm=SHA1");
+        assert_eq!(
+            url,
+            "otpauth://totp/Valve:mock?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Valve&algorithm=SHA1&digits=6&period=30&encoder=steam"
+        );
+        let (decoded, unsupported) = TOTP::from_url_verbose(&url).unwrap();
+        assert!(unsupported.is_empty());
+        assert_eq!(decoded, totp);
+        assert_eq!(totp.to_string().parse::<TOTP>().unwrap(), totp);
     }
 
     #[test]
-    fn url_for_secret_matches_sha1() {
+    fn steam_issuer_url_round_trip() {
         let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            0,
+            30,
+            "TestSecretSuperSecret".as_bytes(),
+            "me".to_string(),
+            Some("Steam".to_string()),
+        )
+        .unwrap();
+        let decoded = TOTP::from_url(totp.get_url()).unwrap();
+        assert!(!decoded.is_steam());
+        assert_eq!(decoded, totp);
+        assert_eq!(decoded.generate(1000).unwrap(), "804420");
+        assert_eq!(totp.to_string().parse::<TOTP>().unwrap(), totp);
+    }
+
+    #[test]
+    fn steam_generators() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock".to_string(),
+            None,
+        )
+        .unwrap()
+        .with_steam(true);
+        let token = totp.generate(1000).unwrap();
+        assert_eq!(token, "RBJNV");
+        assert_eq!(
+            totp.generate_at_counter(totp.counter(1000)).unwrap(),
+            token
+        );
+        assert_eq!(totp.generate_with_color(1000).unwrap().0, token);
+        assert!(totp.verify(&token, 1000));
+    }
+
+    #[test]
+    fn from_url_issuer_special() {
+        let totp = TOTP::from_url("otpauth://totp/Github%40:mock%40example.com?issuer=Github%40&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&algorithm=SHA1").unwrap();
+        let totp_bis = TOTP::new(
             Algorithm::SHA1,
             6,
             1,
-            1,
+            30,
             "TestSecretSuperSecret".as_bytes().to_vec(),
             "mock@example.com".to_string(),
-            Some("Github".to_string()),
+            Some("Github@".to_string()),
         )
         .unwrap();
-        let url = totp.get_url();
-        assert_eq!(url.as_str(), "otpauth://totp/Github:mock%40example.com?issuer=Github&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&algorithm=SHA1");
+        assert_eq!(totp.get_url(), totp_bis.get_url());
+        assert_eq!(totp.issuer.as_ref().unwrap(), "Github@");
     }
 
     #[test]
-    fn url_for_secret_matches_sha256() {
+    #[cfg(feature = "sha2")]
+    fn from_url_duplicate_param() {
+        for (url, param) in [
+            ("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ", "secret"),
+            ("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&digits=999", "digits"),
+            ("otpauth://totp/GitHub:test?period=30&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&period=60", "period"),
+            ("otpauth://totp/GitHub:test?algorithm=SHA1&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&algorithm=SHA256", "algorithm"),
+        ] {
+            assert!(
+                matches!(TOTP::from_url(url), Err(Error::DuplicateParam(p)) if p == param)
+            );
+        }
+        assert!(matches!(
+            TOTP::url_params("otpauth://totp/GitHub:test?digits=6&digits=8"),
+            Err(Error::DuplicateParam(_))
+        ));
+        // Other parameters may be repeated.
+        assert!(TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&foo=1&foo=2").is_ok());
+    }
+
+    #[test]
+    fn from_url_path_only_issuer() {
+        let totp = TOTP::from_url(
+            "otpauth://totp/BigCorp:alice?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+        )
+        .unwrap();
+        assert_eq!(totp.issuer.as_deref(), Some("BigCorp"));
+        assert_eq!(totp.account_name, "alice");
+        assert_eq!(
+            totp.get_url(),
+            "otpauth://totp/BigCorp:alice?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=BigCorp&algorithm=SHA1&digits=6&period=30"
+        );
+        assert_eq!(TOTP::from_url(totp.get_url()).unwrap(), totp);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_url_query_issuer() {
+        let totp = TOTP::from_url("otpauth://totp/GitHub:test?issuer=GitHub&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256").unwrap();
+        assert_eq!(
+            totp.secret,
+            base32::decode(
+                base32::Alphabet::Rfc4648 { padding: false },
+                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
+            )
+            .unwrap()
+        );
+        assert_eq!(totp.algorithm, Algorithm::SHA256);
+        assert_eq!(totp.digits, 8);
+        assert_eq!(totp.skew, 0);
+        assert_eq!(totp.step, 60);
+        assert_eq!(totp.issuer.as_ref().unwrap(), "GitHub");
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_str() {
+        let url = "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256";
+        let totp: TOTP = url.parse().unwrap();
+        assert_eq!(totp.algorithm, Algorithm::SHA256);
+        assert_eq!(totp.digits, 8);
+        assert_eq!(totp.step, 60);
+        let totp_str = TOTP::try_from(url).unwrap();
+        assert_eq!(totp_str.get_url(), totp.get_url());
+        let totp_string = TOTP::try_from(url.to_string()).unwrap();
+        assert_eq!(totp_string.get_url(), totp.get_url());
+        assert!(matches!(
+            "http://totp/GitHub:test".parse::<TOTP>(),
+            Err(Error::Scheme(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_url_wrong_scheme() {
+        let totp = TOTP::from_url("http://totp/GitHub:test?issuer=GitHub&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256");
+        assert!(totp.is_err());
+        let err = totp.unwrap_err();
+        assert!(matches!(err, Error::Scheme(_)));
+    }
+
+    #[test]
+    fn from_url_lenient() {
+        let expected = TOTP::from_url(
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8",
+        )
+        .unwrap();
+        for url in [
+            "otpauth://TOTP/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8",
+            "otpauth://Totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8",
+            "otpauth:///GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8",
+            "otpauth:///TOTP/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8",
+            "otpauth:GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8",
+        ] {
+            assert_eq!(TOTP::from_url_lenient(url).unwrap(), expected, "{}", url);
+        }
+        assert!(TOTP::from_url_lenient("otpauth://STEAM/Steam:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ")
+            .unwrap()
+            .is_steam());
+
+        // The strict parser is unchanged.
+        assert!(matches!(
+            TOTP::from_url("otpauth://TOTP/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"),
+            Err(Error::Host(_))
+        ));
+        assert!(matches!(
+            TOTP::from_url_lenient("otpauth://hotp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"),
+            Err(Error::Host(_))
+        ));
+        assert!(matches!(
+            TOTP::from_url_lenient("https://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"),
+            Err(Error::Scheme(_))
+        ));
+    }
+
+    #[test]
+    fn from_url_no_host() {
+        let totp = TOTP::from_url(
+            "otpauth:///foo?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+        );
+        assert!(matches!(totp.unwrap_err(), Error::Host(_)));
+        let totp = TOTP::from_url(
+            "otpauth:foo?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+        );
+        assert!(matches!(totp.unwrap_err(), Error::Host(_)));
+    }
+
+    #[test]
+    fn from_url_secret_too_small() {
+        let err =
+            TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXE")
+                .unwrap_err();
+        assert!(err.to_string().starts_with("Could not import URL: "));
+        let source = core::error::Error::source(&err).unwrap();
+        assert_eq!(source.to_string(), Error::SecretTooSmall(64).to_string());
+        assert!(matches!(
+            err,
+            Error::UrlImport(e) if matches!(*e, Error::SecretTooSmall(64))
+        ));
+    }
+
+    #[test]
+    fn from_url_digits_out_of_range() {
+        for digits in [0, 5, 9] {
+            let url = format!("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits={}", digits);
+            let err = TOTP::from_url(url).unwrap_err();
+            assert!(matches!(
+                err,
+                Error::UrlImport(e) if matches!(*e, Error::InvalidDigits(d) if d == digits)
+            ));
+        }
+        let err = TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=six").unwrap_err();
+        assert!(matches!(err, Error::Digits(_)));
+    }
+
+    #[test]
+    fn url_issuer_special_characters() {
         let totp = TOTP::new(
-            Algorithm::SHA256,
+            Algorithm::SHA1,
             6,
             1,
-            1,
+            30,
             "TestSecretSuperSecret".as_bytes().to_vec(),
-            "mock@example.com".to_string(),
-            Some("Github".to_string()),
+            "a&b?c/d".to_string(),
+            Some("Acme & Co/?".to_string()),
         )
         .unwrap();
         let url = totp.get_url();
-        assert_eq!(url.as_str(), "otpauth://totp/Github:mock%40example.com?issuer=Github&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&algorithm=SHA256");
+        assert_eq!(url, "otpauth://totp/Acme%20%26%20Co%2F%3F:a%26b%3Fc%2Fd?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Acme%20%26%20Co%2F%3F&algorithm=SHA1&digits=6&period=30");
+        let decoded = TOTP::from_url(url).unwrap();
+        assert_eq!(decoded.issuer.as_deref(), Some("Acme & Co/?"));
+        assert_eq!(decoded.account_name, "a&b?c/d");
+    }
+
+    #[test]
+    fn url_space_and_plus_round_trip() {
+        for (account_name, issuer) in
+            [("John Doe", "Acme Co"), ("a+b", "c+d"), ("a + b", "c+ d")]
+        {
+            let totp = TOTP::new(
+                Algorithm::SHA1,
+                6,
+                1,
+                30,
+                "TestSecretSuperSecret".as_bytes().to_vec(),
+                account_name.to_string(),
+                Some(issuer.to_string()),
+            )
+            .unwrap();
+            let url = totp.get_url();
+            assert!(!url.contains(' '));
+            let decoded = TOTP::from_url(&url).unwrap();
+            assert_eq!(decoded.account_name, account_name);
+            assert_eq!(decoded.issuer.as_deref(), Some(issuer));
+        }
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "John Doe".to_string(),
+            None,
+        )
+        .unwrap();
+        assert!(totp.get_url().starts_with("otpauth://totp/John%20Doe?"));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_url_lowercase_algorithm() {
+        let totp = TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&algorithm=sha256").unwrap();
+        assert_eq!(totp.algorithm, Algorithm::SHA256);
+    }
+
+    #[test]
+    fn from_url_wrong_algo() {
+        let totp = TOTP::from_url("otpauth://totp/GitHub:test?issuer=GitHub&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=MD5");
+        assert!(totp.is_err());
+        let err = totp.unwrap_err();
+        assert!(matches!(err, Error::Algorithm(_)));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_url_query_different_issuers() {
+        let totp = TOTP::from_url("otpauth://totp/GitHub:test?issuer=Gitlab&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256");
+        assert!(totp.is_err());
+        assert!(matches!(totp.unwrap_err(), Error::IssuerMismatch(_, _)));
     }
 
     #[test]
-    fn url_for_secret_matches_sha512() {
-        let totp = TOTP::new(
-            Algorithm::SHA512,
-            6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
-            "mock@example.com".to_string(),
-            Some("Github".to_string()),
+    #[cfg(feature = "normalize")]
+    fn from_url_normalized_issuers() {
+        // Composed `é` in the label, `e` and a combining accent in the query.
+        let totp = TOTP::from_url("otpauth://totp/Caf%C3%A9:test?issuer=Cafe%CC%81&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ").unwrap();
+        assert_eq!(totp.issuer.as_deref(), Some("Caf\u{e9}"));
+
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "Jose\u{301}".to_string(),
+            Some("Cafe\u{301}".to_string()),
         )
         .unwrap();
-        let url = totp.get_url();
-        assert_eq!(url.as_str(), "otpauth://totp/Github:mock%40example.com?issuer=Github&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&algorithm=SHA512");
+        assert_eq!(totp.account_name, "Jos\u{e9}");
+        assert_eq!(totp.issuer.as_deref(), Some("Caf\u{e9}"));
     }
 
     #[test]
-    fn ttl_ok() {
+    #[cfg(feature = "sha2")]
+    fn env_string_round_trip() {
         let totp = TOTP::new(
-            Algorithm::SHA512,
-            6,
-            1,
-            1,
+            Algorithm::SHA256,
+            8,
+            2,
+            60,
             "TestSecretSuperSecret".as_bytes().to_vec(),
-            "mock@example.com".to_string(),
-            Some("Github".to_string()),
+            "mock user@example.com".to_string(),
+            Some("Git Hub & Co/$HOME".to_string()),
         )
         .unwrap();
-        assert!(totp.ttl().is_ok());
+        let value = totp.to_env_string();
+        assert_eq!(value.as_str(), "TOTP_v2:SHA256:8:2:60:KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ:mock%20user%40example.com:Git%20Hub%20%26%20Co%2F%24HOME:0::");
+        let decoded = TOTP::from_env_string(&value).unwrap();
+        assert_eq!(decoded.algorithm, Algorithm::SHA256);
+        assert_eq!(decoded.digits, 8);
+        assert_eq!(decoded.skew, 2);
+        assert_eq!(decoded.step, 60);
+        assert_eq!(decoded.secret, totp.secret);
+        assert_eq!(decoded.account_name, totp.account_name);
+        assert_eq!(decoded.issuer, totp.issuer);
     }
 
     #[test]
-    fn returns_base32() {
-        let totp = TOTP::new(
+    fn env_string_round_trip_every_field() {
+        let mut totp = TOTP::new(
             Algorithm::SHA1,
-            6,
-            1,
-            1,
+            8,
+            2,
+            60,
             "TestSecretSuperSecret".as_bytes().to_vec(),
             "mock@example.com".to_string(),
-            None,
+            Some("Github".to_string()),
         )
-        .unwrap();
+        .unwrap()
+        .with_endianness(Endianness::Little)
+        .with_steam(true);
+        totp.t0 = 1000;
+        totp = totp
+            .with_asymmetric_skew(Some(Skew {
+                before: 3,
+                after: 1,
+            }))
+            .unwrap();
+        totp.image = Some("https://example.com/icon.png".to_string());
+
+        let value = totp.to_env_string();
+        assert_eq!(value.as_str(), "TOTP_v2:SHA1:8:2:60:KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ:mock%40example.com:Github:1000:https%3A%2F%2Fexample.com%2Ficon.png:steam,little-endian,skew=3-1");
+        let decoded = TOTP::from_env_string(&value).unwrap();
+        assert_eq!(decoded, totp);
         assert_eq!(
-            totp.to_secret_base32().as_str(),
-            "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
+            decoded.generate(1059).unwrap(),
+            totp.generate(1059).unwrap()
         );
+
+        assert!(matches!(
+            TOTP::from_env_string(&value.replace("steam,", "steem,")),
+            Err(Error::EnvString(_))
+        ));
+        assert!(matches!(
+            TOTP::from_env_string(&value.replace("skew=3-1", "skew=3")),
+            Err(Error::EnvString(_))
+        ));
     }
 
     #[test]
-    fn generate_token() {
-        let totp = TOTP::new(
-            Algorithm::SHA1,
-            6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
-            "mock@example.com".to_string(),
-            None,
+    fn env_string_no_issuer() {
+        let totp = TOTP::from_env_string(
+            "TOTP_v1:SHA1:6:1:30:KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ:mock:",
         )
         .unwrap();
-        assert_eq!(totp.generate(1000).as_str(), "659761");
+        assert_eq!(totp.account_name.as_str(), "mock");
+        assert!(totp.issuer.is_none());
+        assert!(matches!(
+            TOTP::from_env_string("TOTP_v2:SHA1:6:1:30:ABC:mock:"),
+            Err(Error::EnvString(_))
+        ));
+        assert!(matches!(
+            TOTP::from_env_string("TOTP_v3:SHA1:6:1:30:ABC:mock::0::"),
+            Err(Error::EnvString(_))
+        ));
     }
 
     #[test]
-    fn generate_token_current() {
-        let totp = TOTP::new(
+    fn valid_codes() {
+        let mut totp = TOTP::new(
             Algorithm::SHA1,
             6,
             1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
+            30,
+            "TestSecretSuperSecret".as_bytes(),
             "mock@example.com".to_string(),
             None,
         )
         .unwrap();
-        let time = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let codes = totp.valid_codes(1000).unwrap();
         assert_eq!(
-            totp.generate(time).as_str(),
-            totp.generate_current().unwrap()
+            codes,
+            vec![
+                totp.generate(970).unwrap(),
+                totp.generate(1000).unwrap(),
+                totp.generate(1030).unwrap(),
+            ]
+        );
+        assert!(codes.iter().all(|code| totp.verify(code, 1000)));
+
+        totp = totp
+            .with_asymmetric_skew(Some(Skew {
+                before: 2,
+                after: 0,
+            }))
+            .unwrap();
+        assert_eq!(
+            totp.valid_codes(1000).unwrap(),
+            vec![
+                totp.generate(940).unwrap(),
+                totp.generate(970).unwrap(),
+                totp.generate(1000).unwrap(),
+            ]
         );
+        // The window is clamped at the first step.
+        assert_eq!(totp.valid_codes(0).unwrap().len(), 1);
     }
 
     #[test]
-    fn generates_token_sha256() {
-        let totp = TOTP::new(
-            Algorithm::SHA256,
-            6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
+    #[cfg(feature = "std")]
+    fn valid_codes_current() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
             "mock@example.com".to_string(),
             None,
         )
         .unwrap();
-        assert_eq!(totp.generate(1000).as_str(), "076417");
+        let codes = totp.valid_codes_current().unwrap();
+        assert_eq!(codes.len(), 3);
+        assert!(codes.contains(&totp.generate_current().unwrap()));
     }
 
     #[test]
-    fn generates_token_sha512() {
-        let totp = TOTP::new(
-            Algorithm::SHA512,
-            6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
-            "mock@example.com".to_string(),
-            None,
-        )
-        .unwrap();
-        assert_eq!(totp.generate(1000).as_str(), "473536");
+    fn debug_codes() {
+        let totp = rfc6238(RFC6238_SEED_SHA1, Algorithm::SHA1);
+        let codes = totp.debug_codes(59).unwrap();
+        assert_eq!(codes[0], (Algorithm::SHA1, "94287082".to_string()));
+        assert_eq!(codes.len(), ALGORITHMS.len());
+        for (algorithm, code) in codes {
+            assert_eq!(
+                code,
+                totp.with_algorithm(algorithm).generate(59).unwrap()
+            );
+        }
     }
 
     #[test]
-    fn checks_token() {
-        let totp = TOTP::new(
-            Algorithm::SHA1,
-            6,
-            0,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
+    #[cfg(all(feature = "std", feature = "sha2"))]
+    fn debug_codes_current() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
             "mock@example.com".to_string(),
             None,
         )
         .unwrap();
-        assert!(totp.check("659761", 1000));
+        let algorithms: Vec<Algorithm> = totp
+            .debug_codes_current()
+            .unwrap()
+            .into_iter()
+            .map(|(algorithm, _)| algorithm)
+            .collect();
+        assert_eq!(
+            algorithms,
+            [Algorithm::SHA1, Algorithm::SHA256, Algorithm::SHA512]
+        );
     }
 
     #[test]
-    fn checks_token_current() {
+    #[cfg(feature = "sha2")]
+    fn bytes_round_trip() {
         let totp = TOTP::new(
-            Algorithm::SHA1,
-            6,
-            0,
-            1,
+            Algorithm::SHA256,
+            8,
+            2,
+            60,
             "TestSecretSuperSecret".as_bytes().to_vec(),
             "mock@example.com".to_string(),
-            None,
+            Some("Github".to_string()),
         )
         .unwrap();
-        assert!(totp
-            .check_current(&totp.generate_current().unwrap())
-            .unwrap());
-        assert!(!totp.check_current("bogus").unwrap());
-    }
+        let bytes = totp.to_bytes();
+        assert_eq!(bytes[..4], [2, 1, 8, 2]);
+        assert_eq!(TOTP::from_bytes(&bytes).unwrap(), totp);
 
-    #[test]
-    fn checks_token_with_skew() {
-        let totp = TOTP::new(
-            Algorithm::SHA1,
-            6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
+        // Version 1 has no trailing fields.
+        let mut v1 = bytes[..bytes.len() - 11].to_vec();
+        v1[0] = 1;
+        assert_eq!(TOTP::from_bytes(&v1).unwrap(), totp);
+
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
             "mock@example.com".to_string(),
             None,
         )
         .unwrap();
-        assert!(
-            totp.check("174269", 1000)
-                && totp.check("659761", 1000)
-                && totp.check("260393", 1000)
-        );
+        assert_eq!(TOTP::from_bytes(&totp.to_bytes()).unwrap(), totp);
     }
 
     #[test]
-    fn next_step() {
-        let totp = TOTP::new(
+    fn bytes_round_trip_every_field() {
+        let mut totp = TOTP::new(
             Algorithm::SHA1,
-            6,
-            1,
-            30,
+            8,
+            2,
+            60,
             "TestSecretSuperSecret".as_bytes().to_vec(),
             "mock@example.com".to_string(),
-            Some("Mock Service".to_string()),
-        )
-        .unwrap();
-        assert!(totp.next_step(0) == 30);
-        assert!(totp.next_step(29) == 30);
-        assert!(totp.next_step(30) == 60);
-    }
-
-    #[test]
-    fn from_url_err() {
-        assert!(TOTP::from_url("otpauth://hotp/123").is_err());
-        assert!(TOTP::from_url("otpauth://totp/GitHub:test").is_err());
-        assert!(TOTP::from_url(
-            "otpauth://totp/GitHub:test:?secret=ABC&digits=8&period=60&algorithm=SHA256"
-        )
-        .is_err());
-        assert!(TOTP::from_url("otpauth://totp/Github:mock%40example.com?issuer=GitHub&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&algorithm=SHA1").is_err())
-    }
-
-    #[test]
-    fn from_url_default() {
-        let totp = TOTP::from_url(
-            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            Some("Github".to_string()),
         )
-        .unwrap();
-        assert_eq!(
-            totp.secret,
-            base32::decode(
-                base32::Alphabet::RFC4648 { padding: false },
-                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
-            )
-            .unwrap()
-        );
-        assert_eq!(totp.algorithm, Algorithm::SHA1);
-        assert_eq!(totp.digits, 6);
-        assert_eq!(totp.skew, 1);
-        assert_eq!(totp.step, 30);
-    }
+        .unwrap()
+        .with_endianness(Endianness::Little)
+        .with_steam(true);
+        totp.t0 = 1000;
+        totp = totp
+            .with_asymmetric_skew(Some(Skew {
+                before: 3,
+                after: 1,
+            }))
+            .unwrap();
+        totp.image = Some("https://example.com/icon.png".to_string());
 
-    #[test]
-    fn from_url_query() {
-        let totp = TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256").unwrap();
+        let decoded = TOTP::from_bytes(&totp.to_bytes()).unwrap();
+        assert_eq!(decoded, totp);
         assert_eq!(
-            totp.secret,
-            base32::decode(
-                base32::Alphabet::RFC4648 { padding: false },
-                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
-            )
-            .unwrap()
+            decoded.generate(1059).unwrap(),
+            totp.generate(1059).unwrap()
         );
-        assert_eq!(totp.algorithm, Algorithm::SHA256);
-        assert_eq!(totp.digits, 8);
-        assert_eq!(totp.skew, 1);
-        assert_eq!(totp.step, 60);
-    }
-
-    #[test]
-    fn from_url_query_sha512() {
-        let totp = TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA512").unwrap();
         assert_eq!(
-            totp.secret,
-            base32::decode(
-                base32::Alphabet::RFC4648 { padding: false },
-                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
-            )
-            .unwrap()
+            decoded.generate(1060).unwrap(),
+            totp.generate(1060).unwrap()
         );
-        assert_eq!(totp.algorithm, Algorithm::SHA512);
-        assert_eq!(totp.digits, 8);
-        assert_eq!(totp.skew, 1);
-        assert_eq!(totp.step, 60);
     }
 
     #[test]
-    fn from_url_to_url() {
-        let totp = TOTP::from_url("otpauth://totp/Github:mock%40example.com?issuer=Github&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&algorithm=SHA1").unwrap();
-        let totp_bis = TOTP::new(
-            Algorithm::SHA1,
-            6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
+    fn bytes_malformed() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
             "mock@example.com".to_string(),
-            Some("Github".to_string()),
+            None,
         )
         .unwrap();
-        assert_eq!(totp.get_url(), totp_bis.get_url());
+        let bytes = totp.to_bytes();
+        assert!(matches!(
+            TOTP::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(Error::Decode(_))
+        ));
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(TOTP::from_bytes(&trailing), Err(Error::Decode(_))));
+        let mut version = bytes.clone();
+        version[0] = 3;
+        assert!(matches!(TOTP::from_bytes(&version), Err(Error::Decode(_))));
+        let mut options = bytes.clone();
+        let len = options.len();
+        options[len - 3] = 0x80;
+        assert!(matches!(TOTP::from_bytes(&options), Err(Error::Decode(_))));
+        let mut algorithm = bytes.clone();
+        algorithm[1] = 3;
+        assert!(matches!(
+            TOTP::from_bytes(&algorithm),
+            Err(Error::Decode(_))
+        ));
+        let mut digits = bytes;
+        digits[2] = 5;
+        assert!(matches!(
+            TOTP::from_bytes(&digits),
+            Err(Error::InvalidDigits(5))
+        ));
+        assert!(matches!(TOTP::from_bytes(&[]), Err(Error::Decode(_))));
     }
 
     #[test]
-    fn from_url_unknown_param() {
-        let totp = TOTP::from_url("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256&foo=bar").unwrap();
-        assert_eq!(
-            totp.secret,
-            base32::decode(
-                base32::Alphabet::RFC4648 { padding: false },
-                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
-            )
-            .unwrap()
-        );
-        assert_eq!(totp.algorithm, Algorithm::SHA256);
-        assert_eq!(totp.digits, 8);
-        assert_eq!(totp.skew, 1);
-        assert_eq!(totp.step, 60);
+    #[cfg(feature = "sha2")]
+    fn url_params_placeholder_secret() {
+        let (algorithm, digits, step) = TOTP::url_params("otpauth://totp/GitHub:test?secret=PLACEHOLDER&digits=8&period=60&algorithm=SHA256").unwrap();
+        assert_eq!(algorithm, Algorithm::SHA256);
+        assert_eq!(digits, 8);
+        assert_eq!(step, 60);
     }
 
     #[test]
-    fn from_url_issuer_special() {
-        let totp = TOTP::from_url("otpauth://totp/Github%40:mock%40example.com?issuer=Github%40&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=6&algorithm=SHA1").unwrap();
-        let totp_bis = TOTP::new(
-            Algorithm::SHA1,
-            6,
-            1,
-            1,
-            "TestSecretSuperSecret".as_bytes().to_vec(),
-            "mock@example.com".to_string(),
-            Some("Github@".to_string()),
+    fn url_params_agrees_with_from_url() {
+        let (_, digits, _) = TOTP::url_params(
+            "otpauth://steam/Steam:test?secret=PLACEHOLDER&digits=5",
         )
         .unwrap();
-        assert_eq!(totp.get_url(), totp_bis.get_url());
-        assert_eq!(totp.issuer.as_ref().unwrap(), "Github@");
+        assert_eq!(digits, 6);
+        assert!(matches!(
+            TOTP::url_params("otpauth://totp/GitHub:test?period=0"),
+            Err(Error::UrlImport(e)) if matches!(*e, Error::InvalidStep(0))
+        ));
+        assert!(matches!(
+            TOTP::url_params("otpauth://totp/GitHub:test?digits=9"),
+            Err(Error::UrlImport(e)) if matches!(*e, Error::InvalidDigits(9))
+        ));
+        for param in ["digits=6", "period=30", "algorithm=SHA1"] {
+            let url =
+                format!("otpauth://totp/GitHub:test?{}&{}", param, param);
+            assert!(matches!(
+                TOTP::url_params(&url),
+                Err(Error::DuplicateParam(_))
+            ));
+        }
     }
 
     #[test]
-    fn from_url_query_issuer() {
-        let totp = TOTP::from_url("otpauth://totp/GitHub:test?issuer=GitHub&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256").unwrap();
-        assert_eq!(
-            totp.secret,
-            base32::decode(
-                base32::Alphabet::RFC4648 { padding: false },
-                "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
-            )
-            .unwrap()
-        );
-        assert_eq!(totp.algorithm, Algorithm::SHA256);
-        assert_eq!(totp.digits, 8);
-        assert_eq!(totp.skew, 1);
-        assert_eq!(totp.step, 60);
-        assert_eq!(totp.issuer.as_ref().unwrap(), "GitHub");
+    fn url_params_missing_secret() {
+        let (algorithm, digits, step) =
+            TOTP::url_params("otpauth://totp/GitHub:test").unwrap();
+        assert_eq!(algorithm, Algorithm::SHA1);
+        assert_eq!(digits, 6);
+        assert_eq!(step, 30);
     }
 
     #[test]
-    fn from_url_wrong_scheme() {
-        let totp = TOTP::from_url("http://totp/GitHub:test?issuer=GitHub&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256");
-        assert!(totp.is_err());
-        let err = totp.unwrap_err();
-        assert!(matches!(err, Error::Scheme(_)));
-    }
+    fn validate_url() {
+        for url in [
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8",
+            "otpauth://totp/GitHub:test?secret=krsx%20g5ct%20mvrx%20ezlu%20kn2x%20azls%20knsw%20g4tf%20oq",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ======",
+        ] {
+            TOTP::validate_url(url).unwrap();
+            TOTP::from_url(url).unwrap();
+        }
 
-    #[test]
-    fn from_url_wrong_algo() {
-        let totp = TOTP::from_url("otpauth://totp/GitHub:test?issuer=GitHub&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=MD5");
-        assert!(totp.is_err());
-        let err = totp.unwrap_err();
-        assert!(matches!(err, Error::Algorithm(_)));
+        let err = |url| TOTP::validate_url(url).unwrap_err();
+        assert!(matches!(
+            err("otpauth://hotp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"),
+            Error::Host(_)
+        ));
+        assert!(matches!(
+            err("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=9"),
+            Error::UrlImport(e) if matches!(*e, Error::InvalidDigits(9))
+        ));
+        assert!(matches!(
+            err("otpauth://totp/GitHub:test?digits=8"),
+            Error::Secret(_)
+        ));
+        assert!(matches!(
+            err("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TF01"),
+            Error::Secret(_)
+        ));
+        assert!(matches!(
+            err("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQA"),
+            Error::SecretLength(35)
+        ));
+        assert!(matches!(
+            err("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLU"),
+            Error::UrlImport(e) if matches!(*e, Error::SecretTooSmall(80))
+        ));
+        assert!(matches!(
+            err("otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&period=0"),
+            Error::UrlImport(e) if matches!(*e, Error::InvalidStep(0))
+        ));
+        assert!(matches!(
+            err("otpauth://totp/GitHub:%FF?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"),
+            Error::AccountName(_)
+        ));
+        assert!(matches!(
+            err("otpauth://totp/%FF:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"),
+            Error::IssuerDecoding(_)
+        ));
     }
 
     #[test]
-    fn from_url_query_different_issuers() {
-        let totp = TOTP::from_url("otpauth://totp/GitHub:test?issuer=Gitlab&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=8&period=60&algorithm=SHA256");
-        assert!(totp.is_err());
-        assert!(matches!(totp.unwrap_err(), Error::IssuerMismatch(_, _)));
+    fn validate_url_agrees_with_from_url() {
+        for url in [
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=GitHub",
+            "otpauth://totp/a:b:c?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=GitLab",
+            "otpauth://totp/test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Git%3AHub",
+            "otpauth://totp/Steam:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Steam&digits=5",
+            "otpauth://totp/Steam:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&encoder=steam&digits=5",
+            "otpauth://steam/Steam:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=5",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=5",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=9",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&digits=x",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TF01",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQA",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLU",
+            "otpauth://totp/GitHub:test?secret=KRSX%3DG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            "otpauth://totp/GitHub:test?secret=%20%20",
+            "otpauth://totp/GitHub:test?digits=8",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&period=0",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&algorithm=MD5",
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            "otpauth://totp/GitHub:%FF?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            "otpauth://hotp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            "https://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ",
+            "not a url",
+        ] {
+            let validated = TOTP::validate_url(url);
+            let imported = TOTP::from_url(url).map(|_| ());
+            assert_eq!(
+                validated.as_ref().map_err(|e| e.to_string()),
+                imported.as_ref().map_err(|e| e.to_string()),
+                "{}",
+                url
+            );
+        }
+
+        assert!(TOTP::validate_url(
+            "otpauth://totp/a:b:c?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"
+        )
+        .is_err());
+        assert!(TOTP::validate_url(
+            "otpauth://totp/GitHub:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=GitLab"
+        )
+        .is_err());
+        TOTP::validate_url("otpauth://totp/Steam:test?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Steam&encoder=steam&digits=5").unwrap();
     }
 }
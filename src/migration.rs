@@ -0,0 +1,297 @@
+//! Import accounts exported by Google Authenticator.
+//!
+//! The export is an `otpauth-migration://offline?data=...` URL where
+//! `data` is a base64 encoded protobuf `MigrationPayload` containing
+//! a batch of accounts.
+//!
+//! Requires the `migration` feature.
+use crate::{Algorithm, Error, Result, TOTP};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use url::Url;
+
+/// Standard base64 accepting input with or without padding.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Protobuf wire types.
+const VARINT: u64 = 0;
+const FIXED64: u64 = 1;
+const LENGTH_DELIMITED: u64 = 2;
+const FIXED32: u64 = 5;
+
+/// `OtpType.TOTP` in the payload.
+const OTP_TYPE_TOTP: u64 = 2;
+
+/// Minimal protobuf reader for the fields used by the payload.
+struct Reader<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.buffer.len() {
+            return Err(Error::Migration(
+                "unexpected end of data".to_string(),
+            ));
+        }
+        let (value, rest) = self.buffer.split_at(len);
+        self.buffer = rest;
+        Ok(value)
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::Migration("varint is too long".to_string()))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = usize::try_from(self.varint()?).map_err(|_| {
+            Error::Migration("field length is too large".to_string())
+        })?;
+        self.take(len)
+    }
+
+    /// Read the next field number and wire type.
+    fn key(&mut self) -> Result<(u64, u64)> {
+        let key = self.varint()?;
+        Ok((key >> 3, key & 7))
+    }
+
+    /// Skip the value of a field that is not used.
+    fn skip(&mut self, wire_type: u64) -> Result<()> {
+        match wire_type {
+            VARINT => {
+                self.varint()?;
+            }
+            FIXED64 => {
+                self.take(8)?;
+            }
+            LENGTH_DELIMITED => {
+                self.bytes()?;
+            }
+            FIXED32 => {
+                self.take(4)?;
+            }
+            _ => {
+                return Err(Error::Migration(format!(
+                    "unsupported wire type {}",
+                    wire_type
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+fn utf8(value: &[u8]) -> Result<String> {
+    String::from_utf8(value.to_vec()).map_err(|_| {
+        Error::Migration("string is not valid UTF-8".to_string())
+    })
+}
+
+/// Parse an `OtpParameters` message into a TOTP.
+fn parse_parameters(buffer: &[u8]) -> Result<TOTP> {
+    let mut secret = Vec::new();
+    let mut name = String::new();
+    let mut issuer = String::new();
+    let mut algorithm = Algorithm::SHA1;
+    let mut digits = 6;
+    let mut otp_type = OTP_TYPE_TOTP;
+
+    let mut reader = Reader::new(buffer);
+    while !reader.is_empty() {
+        match reader.key()? {
+            (1, LENGTH_DELIMITED) => secret = reader.bytes()?.to_vec(),
+            (2, LENGTH_DELIMITED) => name = utf8(reader.bytes()?)?,
+            (3, LENGTH_DELIMITED) => issuer = utf8(reader.bytes()?)?,
+            (4, VARINT) => {
+                algorithm = match reader.varint()? {
+                    0 | 1 => Algorithm::SHA1,
+                    #[cfg(feature = "sha2")]
+                    2 => Algorithm::SHA256,
+                    #[cfg(feature = "sha2")]
+                    3 => Algorithm::SHA512,
+                    other => return Err(Error::Algorithm(other.to_string())),
+                }
+            }
+            (5, VARINT) => {
+                digits = match reader.varint()? {
+                    0 | 1 => 6,
+                    2 => 8,
+                    other => return Err(Error::Digits(other.to_string())),
+                }
+            }
+            (6, VARINT) => otp_type = reader.varint()?,
+            (_, wire_type) => reader.skip(wire_type)?,
+        }
+    }
+
+    // Only TOTP accounts can be imported, `OtpType.HOTP` is 1.
+    if otp_type != 0 && otp_type != OTP_TYPE_TOTP {
+        return Err(Error::UnsupportedType(if otp_type == 1 {
+            "hotp".to_string()
+        } else {
+            otp_type.to_string()
+        }));
+    }
+
+    // The name is the label which may be prefixed by the issuer.
+    let account_name = match name.split_once(':') {
+        Some((prefix, account_name)) => {
+            if issuer.is_empty() {
+                issuer = prefix.to_string();
+            }
+            account_name.trim_start().to_string()
+        }
+        None => name,
+    };
+    let issuer = if issuer.is_empty() {
+        None
+    } else {
+        Some(issuer)
+    };
+
+    TOTP::new(algorithm, digits, 0, 30, secret, account_name, issuer)
+}
+
+impl TOTP {
+    /// Generate a TOTP for each account in a Google Authenticator
+    /// `otpauth-migration://offline?data=...` export URL.
+    ///
+    /// Accounts are validated the same as
+    /// [from_url](struct.TOTP.html#method.from_url) so the skew is 0
+    /// and the step is 30 seconds. An unknown algorithm or digit count
+    /// in the payload is reported as an
+    /// [Algorithm](enum.Error.html#variant.Algorithm) or
+    /// [Digits](enum.Error.html#variant.Digits) error and HOTP accounts
+    /// are rejected with an
+    /// [UnsupportedType](enum.Error.html#variant.UnsupportedType) error.
+    pub fn from_migration_url<S: AsRef<str>>(url: S) -> Result<Vec<TOTP>> {
+        let url = Url::parse(url.as_ref())?;
+
+        if url.scheme() != "otpauth-migration" {
+            return Err(Error::Scheme(url.scheme().to_string()));
+        }
+        if url.host_str() != Some("offline") {
+            return Err(Error::Migration(format!(
+                "host should be offline, not '{}'",
+                url.host_str().unwrap_or_default()
+            )));
+        }
+
+        let data = url
+            .query_pairs()
+            .find(|(key, _)| key == "data")
+            .map(|(_, value)| value.replace(' ', "+"))
+            .ok_or_else(|| Error::Migration("missing data".to_string()))?;
+        let payload = BASE64
+            .decode(data)
+            .map_err(|e| Error::Migration(e.to_string()))?;
+
+        let mut totps = Vec::new();
+        let mut reader = Reader::new(&payload);
+        while !reader.is_empty() {
+            match reader.key()? {
+                (1, LENGTH_DELIMITED) => {
+                    totps.push(parse_parameters(reader.bytes()?)?)
+                }
+                (_, wire_type) => reader.skip(wire_type)?,
+            }
+        }
+        Ok(totps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, TOTP};
+    #[cfg(feature = "sha2")]
+    use {
+        crate::Algorithm,
+        alloc::{string::ToString, vec},
+    };
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn from_migration_url() {
+        let totps = TOTP::from_migration_url("otpauth-migration://offline?data=CjcKFVRlc3RTZWNyZXRTdXBlclNlY3JldBIQbW9ja0BleGFtcGxlLmNvbRoGR2l0aHViIAEoATACCisKFk90aGVyU2VjcmV0U3VwZXJTZWNyZXQSCUFjbWU6dXNlchoAIAIoAjACEAEYASAAKLlg").unwrap();
+        assert_eq!(
+            totps,
+            vec![
+                TOTP::new(
+                    Algorithm::SHA1,
+                    6,
+                    0,
+                    30,
+                    "TestSecretSuperSecret".as_bytes(),
+                    "mock@example.com".to_string(),
+                    Some("Github".to_string()),
+                )
+                .unwrap(),
+                TOTP::new(
+                    Algorithm::SHA256,
+                    8,
+                    0,
+                    30,
+                    "OtherSecretSuperSecret".as_bytes(),
+                    "user".to_string(),
+                    Some("Acme".to_string()),
+                )
+                .unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_migration_url_unknown_values() {
+        let err = TOTP::from_migration_url("otpauth-migration://offline?data=CiIKFVRlc3RTZWNyZXRTdXBlclNlY3JldBIBYRoAIAQoATAC").unwrap_err();
+        assert!(matches!(err, Error::Algorithm(_)));
+        let err = TOTP::from_migration_url("otpauth-migration://offline?data=CiIKFVRlc3RTZWNyZXRTdXBlclNlY3JldBIBYRoAIAEoAzAC").unwrap_err();
+        assert!(matches!(err, Error::Digits(_)));
+        let err = TOTP::from_migration_url("otpauth-migration://offline?data=CiIKFVRlc3RTZWNyZXRTdXBlclNlY3JldBIBYRoAIAEoATAB").unwrap_err();
+        assert!(matches!(err, Error::UnsupportedType(ref t) if t == "hotp"));
+    }
+
+    #[test]
+    fn from_migration_url_malformed() {
+        let err = TOTP::from_migration_url(
+            "otpauth://offline?data=CiIKFVRlc3RTZWNyZXRTdXBlclNlY3JldBIBYRoAIAEoATAB",
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Scheme(_)));
+        let err = TOTP::from_migration_url("otpauth-migration://offline")
+            .unwrap_err();
+        assert!(matches!(err, Error::Migration(_)));
+        let err =
+            TOTP::from_migration_url("otpauth-migration://offline?data=CiIK")
+                .unwrap_err();
+        assert!(matches!(err, Error::Migration(_)));
+    }
+}
@@ -0,0 +1,356 @@
+//! Challenge-response tokens as per [rfc-6287](https://tools.ietf.org/html/rfc6287) (OCRA).
+//!
+//! The token is the HMAC of the suite string followed by the
+//! challenge and optionally the time step, truncated the same as
+//! a TOTP token. Suites that include a counter, a password hash
+//! or session information are not supported.
+//!
+//! Requires the `ocra` feature.
+use crate::{truncate, Algorithm, Error, Result};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+/// Length in bytes of the challenge in the data input.
+const CHALLENGE_LEN: usize = 128;
+
+/// Format of the challenge question.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum ChallengeFormat {
+    /// Decimal digits (`QN`).
+    Numeric,
+    /// Alphanumeric characters (`QA`).
+    Alphanumeric,
+    /// Hexadecimal digits (`QH`).
+    Hex,
+}
+
+/// OCRA suite and the secret used to answer challenges.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "zeroize",
+    derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop)
+)]
+pub struct Ocra {
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    suite: String,
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    algorithm: Algorithm,
+    digits: u32,
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    challenge_format: ChallengeFormat,
+    challenge_len: usize,
+    time_step: Option<u64>,
+    secret: Vec<u8>,
+}
+
+impl fmt::Debug for Ocra {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ocra")
+            .field("suite", &self.suite)
+            .field("secret", &"[redacted]")
+            .finish()
+    }
+}
+
+/// Parse the `Q` item of the data input, for example `QN08`.
+fn parse_challenge(item: &str) -> Result<(ChallengeFormat, usize)> {
+    let format = match item.get(1..2) {
+        Some("N") => ChallengeFormat::Numeric,
+        Some("A") => ChallengeFormat::Alphanumeric,
+        Some("H") => ChallengeFormat::Hex,
+        _ => {
+            return Err(Error::Ocra(format!(
+                "unknown challenge format '{}'",
+                item
+            )))
+        }
+    };
+    let len = item
+        .get(2..)
+        .filter(|len| len.len() == 2)
+        .and_then(|len| len.parse::<usize>().ok())
+        .filter(|len| (4..=64).contains(len))
+        .ok_or_else(|| {
+            Error::Ocra(format!("invalid challenge length '{}'", item))
+        })?;
+    Ok((format, len))
+}
+
+/// Parse the `T` item of the data input, for example `T1M`, into
+/// the time step in seconds.
+fn parse_time_step(item: &str) -> Result<u64> {
+    let invalid = || Error::Ocra(format!("invalid time step '{}'", item));
+    if item.len() < 3 || !item.is_ascii() {
+        return Err(invalid());
+    }
+    let (value, unit) = item[1..].split_at(item.len() - 2);
+    let value = value.parse::<u64>().map_err(|_| invalid())?;
+    let (max, seconds) = match unit {
+        "S" => (59, 1),
+        "M" => (59, 60),
+        "H" => (48, 3600),
+        _ => return Err(invalid()),
+    };
+    if value == 0 || value > max {
+        return Err(invalid());
+    }
+    Ok(value * seconds)
+}
+
+/// Convert a decimal number to uppercase hexadecimal digits.
+fn decimal_to_hex(decimal: &[u8]) -> Vec<u8> {
+    let mut digits: Vec<u8> = decimal.iter().map(|b| b - b'0').collect();
+    let mut hex = Vec::new();
+    while digits.iter().any(|d| *d != 0) {
+        let mut remainder = 0u8;
+        for digit in digits.iter_mut() {
+            let value = remainder * 10 + *digit;
+            *digit = value / 16;
+            remainder = value % 16;
+        }
+        hex.push(b"0123456789ABCDEF"[remainder as usize]);
+    }
+    if hex.is_empty() {
+        hex.push(b'0');
+    }
+    hex.reverse();
+    hex
+}
+
+impl Ocra {
+    /// Create a new instance for the suite string, for example
+    /// `OCRA-1:HOTP-SHA1-6:QN08-T1M`.
+    ///
+    /// The crypto function must be HOTP with SHA1, SHA256 or SHA512
+    /// and 4 to 10 digits and the data input must contain a challenge
+    /// which may be followed by a time step; any other suite is
+    /// rejected with an [Ocra](enum.Error.html#variant.Ocra) error.
+    pub fn new(suite: &str, secret: impl Into<Vec<u8>>) -> Result<Ocra> {
+        let unsupported =
+            |item: &str| Error::Ocra(format!("unsupported '{}'", item));
+
+        let parts: Vec<&str> = suite.split(':').collect();
+        if parts.len() != 3 || parts[0] != "OCRA-1" {
+            return Err(Error::Ocra(format!("invalid suite '{}'", suite)));
+        }
+
+        let function: Vec<&str> = parts[1].split('-').collect();
+        if function.len() != 3 || function[0] != "HOTP" {
+            return Err(unsupported(parts[1]));
+        }
+        let algorithm: Algorithm = function[1].parse()?;
+        let digits = function[2]
+            .parse::<u32>()
+            .ok()
+            .filter(|digits| (4..=10).contains(digits))
+            .ok_or_else(|| unsupported(parts[1]))?;
+
+        let mut challenge = None;
+        let mut time_step = None;
+        for item in parts[2].split('-') {
+            match item.get(..1) {
+                Some("Q") if challenge.is_none() && time_step.is_none() => {
+                    challenge = Some(parse_challenge(item)?);
+                }
+                Some("T") if challenge.is_some() && time_step.is_none() => {
+                    time_step = Some(parse_time_step(item)?);
+                }
+                _ => return Err(unsupported(item)),
+            }
+        }
+        let (challenge_format, challenge_len) =
+            challenge.ok_or_else(|| unsupported(parts[2]))?;
+
+        Ok(Ocra {
+            suite: suite.to_string(),
+            algorithm,
+            digits,
+            challenge_format,
+            challenge_len,
+            time_step,
+            secret: secret.into(),
+        })
+    }
+
+    /// The suite string.
+    pub fn suite(&self) -> &str {
+        &self.suite
+    }
+
+    /// Encode the challenge into the 128 bytes of the data input.
+    fn challenge(&self, challenge: &[u8]) -> Result<[u8; CHALLENGE_LEN]> {
+        let invalid = || {
+            Error::Ocra(format!(
+                "challenge '{}' does not match the suite",
+                String::from_utf8_lossy(challenge)
+            ))
+        };
+        if challenge.is_empty() || challenge.len() > self.challenge_len {
+            return Err(invalid());
+        }
+
+        let mut buffer = [0u8; CHALLENGE_LEN];
+        match self.challenge_format {
+            ChallengeFormat::Alphanumeric => {
+                if !challenge.iter().all(|b| b.is_ascii_alphanumeric()) {
+                    return Err(invalid());
+                }
+                buffer[..challenge.len()].copy_from_slice(challenge);
+            }
+            ChallengeFormat::Numeric | ChallengeFormat::Hex => {
+                let hex = if self.challenge_format == ChallengeFormat::Numeric
+                {
+                    if !challenge.iter().all(|b| b.is_ascii_digit()) {
+                        return Err(invalid());
+                    }
+                    decimal_to_hex(challenge)
+                } else {
+                    if !challenge.iter().all(|b| b.is_ascii_hexdigit()) {
+                        return Err(invalid());
+                    }
+                    challenge.to_vec()
+                };
+                // The hex digits are left aligned and padded with zeros.
+                for (i, digit) in hex.iter().enumerate() {
+                    let nibble = (*digit as char).to_digit(16).unwrap() as u8;
+                    buffer[i / 2] |=
+                        if i % 2 == 0 { nibble << 4 } else { nibble };
+                }
+            }
+        }
+        Ok(buffer)
+    }
+
+    /// Generate the response to the challenge given the provided
+    /// timestamp in seconds.
+    ///
+    /// The timestamp is ignored when the suite does not include a
+    /// time step.
+    pub fn generate(&self, challenge: &[u8], time: u64) -> Result<String> {
+        let mut data = Vec::with_capacity(self.suite.len() + 1 + 128 + 8);
+        data.extend_from_slice(self.suite.as_bytes());
+        data.push(0);
+        data.extend_from_slice(&self.challenge(challenge)?);
+        if let Some(step) = self.time_step {
+            data.extend_from_slice(&(time / step).to_be_bytes());
+        }
+
+        let result = truncate(&self.algorithm.sign(&self.secret, &data)?);
+        Ok(format!(
+            "{1:00$}",
+            self.digits as usize,
+            result as u64 % 10_u64.pow(self.digits)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 20 byte key from [rfc-6287](https://tools.ietf.org/html/rfc6287#appendix-C).
+    const SEED_20: &[u8] = b"12345678901234567890";
+
+    /// 64 byte key from [rfc-6287](https://tools.ietf.org/html/rfc6287#appendix-C).
+    #[cfg(feature = "sha2")]
+    const SEED_64: &[u8] =
+        b"1234567890123456789012345678901234567890123456789012345678901234";
+
+    #[test]
+    fn one_way_challenge_response() {
+        let ocra = Ocra::new("OCRA-1:HOTP-SHA1-6:QN08", SEED_20).unwrap();
+        for (challenge, expected) in [
+            ("00000000", "237653"),
+            ("11111111", "243178"),
+            ("22222222", "653583"),
+            ("33333333", "740991"),
+            ("44444444", "608993"),
+            ("55555555", "388898"),
+            ("66666666", "816933"),
+            ("77777777", "224598"),
+            ("88888888", "750600"),
+            ("99999999", "294470"),
+        ] {
+            assert_eq!(
+                ocra.generate(challenge.as_bytes(), 0).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn one_way_challenge_response_time() {
+        // Time step 0x132d0b6 minutes.
+        let time = 0x132d0b6 * 60;
+        let ocra =
+            Ocra::new("OCRA-1:HOTP-SHA512-8:QN08-T1M", SEED_64).unwrap();
+        for (challenge, expected) in [
+            ("00000000", "95209754"),
+            ("11111111", "55907591"),
+            ("22222222", "22048402"),
+            ("33333333", "24218844"),
+            ("44444444", "36209546"),
+        ] {
+            assert_eq!(
+                ocra.generate(challenge.as_bytes(), time).unwrap(),
+                expected
+            );
+            assert_eq!(
+                ocra.generate(challenge.as_bytes(), time + 59).unwrap(),
+                expected
+            );
+        }
+
+        let ocra = Ocra::new("OCRA-1:HOTP-SHA1-6:QN08-T1M", SEED_20).unwrap();
+        assert_ne!(
+            ocra.generate(b"00000000", 0).unwrap(),
+            ocra.generate(b"00000000", 60).unwrap()
+        );
+    }
+
+    #[test]
+    fn unsupported_suites() {
+        for suite in [
+            "OCRA-2:HOTP-SHA1-6:QN08",
+            "OCRA-1:HOTP-SHA1-6",
+            "OCRA-1:HOTP-MD5-6:QN08",
+            "OCRA-1:HOTP-SHA1-3:QN08",
+            "OCRA-1:HOTP-SHA512-8:C-QN08-PSHA1",
+            "OCRA-1:HOTP-SHA1-6:QN08-PSHA1",
+            "OCRA-1:HOTP-SHA1-6:QX08",
+            "OCRA-1:HOTP-SHA1-6:QN65",
+            "OCRA-1:HOTP-SHA1-6:QN08-T0M",
+            "OCRA-1:HOTP-SHA1-6:T1M",
+            "OCRA-1:HOTP-SHA1-6:QN08-T1\u{e9}",
+            "OCRA-1:HOTP-SHA1-6:QN08-T\u{e9}M",
+            "OCRA-1:HOTP-SHA1-\u{e9}:QN08",
+            "OCRA-1:HOTP-SHA1-6:Q\u{e9}08",
+            "OCRA-1:HOTP-SHA1-6:QN\u{e9}",
+        ] {
+            assert!(Ocra::new(suite, SEED_20).is_err(), "{}", suite);
+        }
+    }
+
+    #[test]
+    fn invalid_challenge() {
+        let ocra = Ocra::new("OCRA-1:HOTP-SHA1-6:QN08", SEED_20).unwrap();
+        assert!(matches!(
+            ocra.generate(b"123456789", 0),
+            Err(Error::Ocra(_))
+        ));
+        assert!(matches!(ocra.generate(b"1234abcd", 0), Err(Error::Ocra(_))));
+        assert!(matches!(ocra.generate(b"", 0), Err(Error::Ocra(_))));
+    }
+
+    #[test]
+    fn decimal_to_hex() {
+        assert_eq!(super::decimal_to_hex(b"00000000"), b"0");
+        assert_eq!(super::decimal_to_hex(b"11111111"), b"A98AC7");
+        assert_eq!(super::decimal_to_hex(b"255"), b"FF");
+    }
+}
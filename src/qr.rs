@@ -0,0 +1,70 @@
+//! Render the otpauth URL as a QR code.
+//!
+//! Requires the `qr` feature.
+use crate::{Error, Result, TOTP};
+use alloc::{string::String, vec::Vec};
+use image::{DynamicImage, ImageFormat, Luma};
+use qrcode::{render::svg, QrCode};
+use std::io::Cursor;
+
+impl TOTP {
+    fn qr_code(&self) -> Result<QrCode> {
+        QrCode::new(self.get_url().as_bytes())
+            .map_err(|e| Error::Qr(e.to_string()))
+    }
+
+    /// Render the [otpauth URL](struct.TOTP.html#method.get_url)
+    /// as a QR code encoded as PNG bytes.
+    pub fn get_qr_png(&self) -> Result<Vec<u8>> {
+        let image = self.qr_code()?.render::<Luma<u8>>().build();
+        let mut buffer = Vec::new();
+        DynamicImage::ImageLuma8(image)
+            .write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png)
+            .map_err(|e| Error::Qr(e.to_string()))?;
+        Ok(buffer)
+    }
+
+    /// Render the [otpauth URL](struct.TOTP.html#method.get_url)
+    /// as a QR code encoded as an SVG document.
+    pub fn get_qr_svg(&self) -> Result<String> {
+        Ok(self.qr_code()?.render::<svg::Color>().build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Algorithm, Error, TOTP};
+
+    fn totp(account_name: String) -> TOTP {
+        TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes().to_vec(),
+            account_name,
+            Some("Github".to_string()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn generates_png() {
+        let png = totp("mock@example.com".to_string()).get_qr_png().unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn generates_svg() {
+        let svg = totp("mock@example.com".to_string()).get_qr_svg().unwrap();
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("<svg"));
+    }
+
+    #[test]
+    fn url_too_long() {
+        let totp = totp("a".repeat(8192));
+        assert!(matches!(totp.get_qr_png(), Err(Error::Qr(_))));
+        assert!(matches!(totp.get_qr_svg(), Err(Error::Qr(_))));
+    }
+}
@@ -0,0 +1,57 @@
+//! Buffer for sensitive bytes that is cleared when dropped.
+use alloc::vec::Vec;
+use core::{fmt, ops::Deref};
+
+/// Sensitive bytes such as an HMAC signature.
+///
+/// When the `zeroize` feature is enabled the bytes are
+/// overwritten with zeros when the value is dropped so
+/// intermediate copies derived from the secret do not
+/// linger in memory.
+#[derive(Clone)]
+pub struct Secret(Vec<u8>);
+
+impl Secret {
+    /// Consume the wrapper and return the bytes.
+    ///
+    /// The returned vector is no longer cleared on drop.
+    pub fn into_inner(mut self) -> Vec<u8> {
+        core::mem::take(&mut self.0)
+    }
+}
+
+impl From<Vec<u8>> for Secret {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+impl Deref for Secret {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Secret {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([redacted])")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Secret {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Secret {}
@@ -0,0 +1,118 @@
+//! Serialize a TOTP as its otpauth URL.
+//!
+//! Use with `#[serde(with = "totp_sos::serde_url")]` on a `TOTP`
+//! field to store the account as a single string, the same as the
+//! URL imported from a QR code:
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! # use totp_sos::TOTP;
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "totp_sos::serde_url")]
+//!     totp: TOTP,
+//! }
+//! ```
+//!
+//! The URL is created by [get_url](../struct.TOTP.html#method.get_url)
+//! and read by [from_url](../struct.TOTP.html#method.from_url) so only
+//! the values in the URL are kept; the skew is 0 after deserializing.
+//!
+//! Requires the `serde` feature.
+use crate::TOTP;
+use alloc::string::String;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+/// Serialize the TOTP as its otpauth URL.
+pub fn serialize<S>(totp: &TOTP, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&totp.get_url())
+}
+
+/// Deserialize a TOTP from an otpauth URL.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<TOTP, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let url = String::deserialize(deserializer)?;
+    TOTP::from_url(url).map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TOTP;
+    use alloc::string::ToString;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Config {
+        #[serde(with = "crate::serde_url")]
+        totp: TOTP,
+    }
+
+    #[test]
+    fn url_round_trip() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            Some("Github".to_string()),
+        )
+        .unwrap()
+        .with_skew(0)
+        .unwrap();
+        let json =
+            serde_json::to_string(&Config { totp: totp.clone() }).unwrap();
+        assert_eq!(
+            json,
+            r#"{"totp":"otpauth://totp/Github:mock%40example.com?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ&issuer=Github&algorithm=SHA1&digits=6&period=30"}"#
+        );
+        let config: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(config.totp, totp);
+    }
+
+    #[test]
+    fn steam_round_trip() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock".to_string(),
+            Some("Valve".to_string()),
+        )
+        .unwrap()
+        .with_skew(0)
+        .unwrap()
+        .with_steam(true);
+        let json =
+            serde_json::to_string(&Config { totp: totp.clone() }).unwrap();
+        assert!(json.ends_with(r#"&encoder=steam"}"#));
+        let config: Config = serde_json::from_str(&json).unwrap();
+        assert!(config.totp.is_steam());
+        assert_eq!(config.totp, totp);
+    }
+
+    #[test]
+    fn steam_issuer_round_trip() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock".to_string(),
+            Some("Steam".to_string()),
+        )
+        .unwrap()
+        .with_skew(0)
+        .unwrap();
+        let json =
+            serde_json::to_string(&Config { totp: totp.clone() }).unwrap();
+        let config: Config = serde_json::from_str(&json).unwrap();
+        assert!(!config.totp.is_steam());
+        assert_eq!(config.totp, totp);
+    }
+
+    #[test]
+    fn invalid_url() {
+        assert!(serde_json::from_str::<Config>(
+            r#"{"totp":"otpauth://hotp/Github:mock?secret=KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ"}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! TOTP with the number of digits encoded in the type.
+use crate::{Algorithm, Error, Result, STEAM_DIGITS, TOTP};
+use alloc::{format, string::String, vec::Vec};
+use core::ops::Deref;
+
+/// TOTP where the number of digits is the const parameter `N`.
+///
+/// `N` must be between 6 and 8 which is checked when the
+/// program is compiled, using `Totp::<5>` is a compile error.
+///
+/// Dereferences to the dynamic [TOTP](struct.TOTP.html) for read
+/// access; the fields cannot be changed so the
+/// [digits](struct.TOTP.html#structfield.digits) always equal `N`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Totp<const N: usize> {
+    inner: TOTP,
+}
+
+impl<const N: usize> Totp<N> {
+    const VALID_DIGITS: () =
+        assert!(N >= 6 && N <= 8, "digits must be between 6 and 8");
+
+    /// Create a new instance with the given parameters.
+    ///
+    /// The validation is the same as [TOTP::new](struct.TOTP.html#method.new)
+    /// except for the digits which are checked at compile time.
+    pub fn new(
+        algorithm: Algorithm,
+        skew: u8,
+        step: u64,
+        secret: impl Into<Vec<u8>>,
+        account_name: String,
+        issuer: Option<String>,
+    ) -> Result<Self> {
+        let () = Self::VALID_DIGITS;
+        Ok(Self {
+            inner: TOTP::new(
+                algorithm,
+                N,
+                skew,
+                step,
+                secret,
+                account_name,
+                issuer,
+            )?,
+        })
+    }
+
+    /// Generate a token given the provided timestamp in seconds.
+    pub fn generate(&self, time: u64) -> Result<String> {
+        let result = self.inner.truncated(time)? % 10_u32.pow(N as u32);
+        Ok(format!("{:01$}", result, N))
+    }
+
+    /// Convert into the dynamic [TOTP](struct.TOTP.html).
+    pub fn into_inner(self) -> TOTP {
+        self.inner
+    }
+}
+
+impl<const N: usize> Deref for Totp<N> {
+    type Target = TOTP;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<const N: usize> TryFrom<TOTP> for Totp<N> {
+    type Error = Error;
+
+    /// Returns [Error::InvalidDigits](enum.Error.html#variant.InvalidDigits)
+    /// when the digits of the TOTP are not `N` or when it is in
+    /// [Steam mode](struct.TOTP.html#method.is_steam) as Steam Guard
+    /// tokens are five characters.
+    fn try_from(value: TOTP) -> Result<Self> {
+        let () = Self::VALID_DIGITS;
+        if value.is_steam() {
+            return Err(Error::InvalidDigits(STEAM_DIGITS));
+        }
+        if value.digits != N {
+            return Err(Error::InvalidDigits(value.digits.get()));
+        }
+        Ok(Self { inner: value })
+    }
+}
+
+impl<const N: usize> From<Totp<N>> for TOTP {
+    fn from(value: Totp<N>) -> Self {
+        value.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn generates_with_const_digits() {
+        let totp = Totp::<8>::new(
+            Algorithm::SHA1,
+            1,
+            30,
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(totp.digits, 8);
+        let token = totp.generate(1000).unwrap();
+        assert_eq!(token.len(), 8);
+        assert_eq!(token, totp.inner.generate(1000).unwrap());
+        assert!(totp.verify(&token, 1000));
+    }
+
+    #[test]
+    fn converts_to_and_from_totp() {
+        let totp = TOTP::new_default(
+            "TestSecretSuperSecret".as_bytes(),
+            "mock@example.com".to_string(),
+            None,
+        )
+        .unwrap();
+        let typed = Totp::<6>::try_from(totp.clone()).unwrap();
+        assert_eq!(TOTP::from(typed), totp);
+        assert!(matches!(
+            Totp::<8>::try_from(totp.clone()).unwrap_err(),
+            Error::InvalidDigits(6)
+        ));
+        assert!(matches!(
+            Totp::<6>::try_from(totp.with_steam(true)).unwrap_err(),
+            Error::InvalidDigits(5)
+        ));
+    }
+}